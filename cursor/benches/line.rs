@@ -0,0 +1,29 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::hint::black_box;
+use std::io::Cursor;
+
+fn line(c: &mut Criterion) {
+    let mut group = c.benchmark_group("line");
+    for len in [16, 1024, 16 * 1024] {
+        let mut buf = vec![b'a'; len];
+        buf.extend_from_slice(b"\r\n");
+
+        group.throughput(Throughput::Bytes(buf.len() as u64));
+        group.bench_with_input(BenchmarkId::new("cursor", len), &buf[..], |b, buf| {
+            b.iter(|| cursor::line(&mut Cursor::new(black_box(buf))).unwrap());
+        });
+        // A byte-by-byte scan to compare against, whichever search is enabled
+        group.bench_with_input(BenchmarkId::new("naive", len), &buf[..], |b, buf| {
+            b.iter(|| naive_line(black_box(buf)).unwrap());
+        });
+    }
+    group.finish();
+}
+
+fn naive_line(buf: &[u8]) -> Option<&[u8]> {
+    let end = buf.windows(2).position(|pair| pair == b"\r\n")?;
+    Some(&buf[..end])
+}
+
+criterion_group!(benches, line);
+criterion_main!(benches);
@@ -0,0 +1,157 @@
+//! Parsing functions over any [`Buf`], enabled with the `bytes` feature.
+//!
+//! These look at the first [`chunk`](Buf::chunk) of the buffer, which for
+//! contiguous buffers like [`Bytes`] and [`BytesMut`](bytes::BytesMut) is
+//! everything that's buffered. Slices are returned as [`Bytes`], which avoids
+//! copying when the underlying buffer is itself a `Bytes` or `BytesMut`.
+//!
+//! Each function advances the buffer exactly as far as the function of the
+//! same name in the crate root would advance a cursor.
+use crate::Cursor;
+use crate::CursorError;
+use bytes::{Buf, Bytes};
+use core::ops::Range;
+
+/// Run `parse` over the first chunk of a buffer, advancing the buffer as far
+/// as `parse` advanced the cursor.
+///
+/// This allows any of the parsing functions in this crate to be used on a
+/// [`Buf`] as long as they return owned values.
+///
+/// Those functions have to be wrapped in a closure, like `|src| integer(src)`
+/// instead of just `integer`. `parse` has to accept a cursor borrowing the
+/// chunk for any lifetime, but a generic function item is only generic over
+/// one specific lifetime of the buffer it takes. Writing out the closure's
+/// argument type keeps Clippy from suggesting the function item anyways.
+///
+/// # Examples
+/// ```
+/// # use bytes::{Buf, Bytes};
+/// # use cursor::{buf::with_chunk, integer, CursorError};
+/// let mut src = Bytes::from_static(b"-7\r\nrest");
+///
+/// assert_eq!(with_chunk(&mut src, |src| integer(src)), Ok(-7));
+/// assert_eq!(src.chunk(), b"rest");
+/// ```
+pub fn with_chunk<T>(
+    src: &mut impl Buf,
+    parse: impl FnOnce(&mut Cursor<&[u8]>) -> Result<T, CursorError>,
+) -> Result<T, CursorError> {
+    let mut cursor = Cursor::new(src.chunk());
+    let result = parse(&mut cursor);
+    let pos = cursor.position() as usize;
+    src.advance(pos);
+    result
+}
+
+/// Read a `\r\n`-terminated line from a buffer. See [`line`](crate::line).
+///
+/// # Examples
+/// ```
+/// # use bytes::{Buf, BytesMut};
+/// # use cursor::{buf, CursorError};
+/// let mut src = BytesMut::from(&b"Hello, world!\r\nHel"[..]);
+///
+/// assert_eq!(buf::line(&mut src), Ok("Hello, world!".into()));
+/// assert_eq!(buf::line(&mut src), Err(CursorError::Unterminated(3)));
+/// ```
+pub fn line(src: &mut impl Buf) -> Result<Bytes, CursorError> {
+    split(src, |cursor| {
+        let start = cursor.position() as usize;
+        let line = crate::line(cursor)?;
+        Ok(start..start + line.len())
+    })
+}
+
+/// Read an ASCII-encoded, `\r\n`-terminated decimal size from a buffer.
+/// See [`size`](crate::size).
+///
+/// # Examples
+/// ```
+/// # use bytes::Bytes;
+/// # use cursor::{buf, CursorError};
+/// let mut src = Bytes::from_static(b"100\r\n");
+///
+/// assert_eq!(buf::size(&mut src), Ok(100));
+/// ```
+pub fn size(src: &mut impl Buf) -> Result<u64, CursorError> {
+    with_chunk(src, |cursor: &mut Cursor<&[u8]>| crate::size(cursor))
+}
+
+/// Read an ASCII-encoded, `\r\n`-terminated 64-bit signed integer from a buffer.
+/// See [`integer`](crate::integer).
+///
+/// # Examples
+/// ```
+/// # use bytes::Bytes;
+/// # use cursor::{buf, CursorError};
+/// let mut src = Bytes::from_static(b"-100\r\n");
+///
+/// assert_eq!(buf::integer(&mut src), Ok(-100));
+/// ```
+pub fn integer(src: &mut impl Buf) -> Result<i64, CursorError> {
+    with_chunk(src, |cursor: &mut Cursor<&[u8]>| crate::integer(cursor))
+}
+
+/// Read `len` bytes from a buffer. See [`slice()`](crate::slice()).
+///
+/// # Examples
+/// ```
+/// # use bytes::Bytes;
+/// # use cursor::{buf, CursorError};
+/// let mut src = Bytes::from_static(b"Hello, world!");
+///
+/// assert_eq!(buf::slice(&mut src, 5), Ok("Hello".into()));
+/// assert_eq!(buf::slice(&mut src, 20), Err(CursorError::Incomplete));
+/// ```
+pub fn slice(src: &mut impl Buf, len: u64) -> Result<Bytes, CursorError> {
+    split(src, |cursor| {
+        let start = cursor.position() as usize;
+        crate::slice(cursor, len)?;
+        Ok(start..cursor.position() as usize)
+    })
+}
+
+/// Read a size-prefixed payload from a buffer. See [`bulk`](crate::bulk).
+///
+/// # Examples
+/// ```
+/// # use bytes::BytesMut;
+/// # use cursor::{buf, CursorError};
+/// let mut src = BytesMut::from(&b"5\r\nHello\r\n5\r\nHel"[..]);
+///
+/// assert_eq!(buf::bulk(&mut src), Ok("Hello".into()));
+/// assert_eq!(buf::bulk(&mut src), Err(CursorError::Incomplete));
+/// assert_eq!(&src[..], b"5\r\nHel");
+/// ```
+pub fn bulk(src: &mut impl Buf) -> Result<Bytes, CursorError> {
+    split(src, |cursor| {
+        let payload = crate::bulk(cursor)?;
+        let end = cursor.position() as usize - 2;
+        Ok(end - payload.len()..end)
+    })
+}
+
+/// Run `parse` over the first chunk of a buffer, splitting off the range of
+/// the chunk that it returns and advancing past the rest of what it read.
+fn split(
+    src: &mut impl Buf,
+    parse: impl FnOnce(&mut Cursor<&[u8]>) -> Result<Range<usize>, CursorError>,
+) -> Result<Bytes, CursorError> {
+    let mut cursor = Cursor::new(src.chunk());
+    let result = parse(&mut cursor);
+    let end = cursor.position() as usize;
+
+    match result {
+        Ok(range) => {
+            src.advance(range.start);
+            let bytes = src.copy_to_bytes(range.len());
+            src.advance(end - range.end);
+            Ok(bytes)
+        }
+        Err(err) => {
+            src.advance(end);
+            Err(err)
+        }
+    }
+}
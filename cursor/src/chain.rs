@@ -0,0 +1,302 @@
+//! Reading from a sequence of non-contiguous slices.
+
+use crate::CursorError;
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+/// A cursor over a sequence of slices, as if they were concatenated.
+///
+/// This offers the same reads as the free functions in this crate, but for
+/// data that arrives in chunks, like a list of `Bytes`, without first copying
+/// it all into one buffer. Values may span chunk boundaries, so methods that
+/// would return a slice of the buffer return a [`Cow`] instead, which only
+/// allocates when the value actually spans more than one chunk.
+///
+/// Like the free functions, a method that fails doesn't advance the cursor,
+/// unless noted otherwise.
+///
+/// # Examples
+/// ```
+/// # use cursor::chain::ChainedCursor;
+/// let chunks: [&[u8]; 3] = [b"5\r", b"\nHel", b"lo\r\n"];
+/// let mut src = ChainedCursor::new(&chunks);
+///
+/// assert_eq!(src.bulk().unwrap(), &b"Hello"[..]);
+/// assert_eq!(src.remaining(), 0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ChainedCursor<'a> {
+    chunks: &'a [&'a [u8]],
+    pos: usize,
+}
+
+impl<'a> ChainedCursor<'a> {
+    /// Creates a new [`ChainedCursor`] at the start of `chunks`.
+    pub fn new(chunks: &'a [&'a [u8]]) -> Self {
+        ChainedCursor { chunks, pos: 0 }
+    }
+
+    /// Returns the current position, counting from the start of the first
+    /// chunk.
+    pub fn position(&self) -> u64 {
+        self.pos as u64
+    }
+
+    /// Sets the position, counting from the start of the first chunk.
+    pub fn set_position(&mut self, pos: u64) {
+        self.pos = pos as usize;
+    }
+
+    /// Returns the number of bytes after the position.
+    pub fn remaining(&self) -> usize {
+        self.pieces().map(<[u8]>::len).sum()
+    }
+
+    /// Returns `true` if there are any bytes after the position.
+    pub fn has_remaining(&self) -> bool {
+        self.pieces().next().is_some()
+    }
+
+    /// Read a byte, advancing the position past it.
+    ///
+    /// # Errors
+    ///
+    /// If there are no bytes left, `CursorError::Incomplete` is returned.
+    pub fn byte(&mut self) -> Result<u8, CursorError> {
+        let b = self.peek_byte()?;
+        self.pos += 1;
+        Ok(b)
+    }
+
+    /// Read a byte without moving the position.
+    ///
+    /// # Errors
+    ///
+    /// If there are no bytes left, `CursorError::Incomplete` is returned.
+    pub fn peek_byte(&self) -> Result<u8, CursorError> {
+        self.pieces()
+            .next()
+            .map(|piece| piece[0])
+            .ok_or(CursorError::Incomplete)
+    }
+
+    /// Read `len` bytes, advancing the position past them.
+    ///
+    /// # Errors
+    ///
+    /// If there aren't `len` bytes left, `CursorError::Incomplete` is
+    /// returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::borrow::Cow;
+    /// # use cursor::chain::ChainedCursor;
+    /// let chunks: [&[u8]; 2] = [b"abc", b"def"];
+    /// let mut src = ChainedCursor::new(&chunks);
+    ///
+    /// // Within a chunk, nothing is copied
+    /// assert!(matches!(src.slice(2), Ok(Cow::Borrowed(b"ab"))));
+    /// // Across chunks, the bytes are copied
+    /// assert!(matches!(src.slice(2), Ok(Cow::Owned(v)) if v == b"cd"));
+    /// ```
+    pub fn slice(&mut self, len: usize) -> Result<Cow<'a, [u8]>, CursorError> {
+        let slice = self.get(self.pos, len)?;
+        self.pos += len;
+        Ok(slice)
+    }
+
+    /// Skip `len` bytes, advancing the position past them.
+    ///
+    /// # Errors
+    ///
+    /// If there aren't `len` bytes left, `CursorError::Incomplete` is
+    /// returned.
+    pub fn skip(&mut self, len: usize) -> Result<(), CursorError> {
+        if self.remaining() < len {
+            return Err(CursorError::Incomplete);
+        }
+        self.pos += len;
+        Ok(())
+    }
+
+    /// Read a `\r\n`-terminated line, advancing the position just past the
+    /// `\n`. The `\r\n` may be split between chunks.
+    ///
+    /// # Errors
+    ///
+    /// If there's no terminating `\r\n`, then `CursorError::Unterminated` is
+    /// returned with the number of bytes in the line so far.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cursor::chain::ChainedCursor;
+    /// # use cursor::CursorError;
+    /// let chunks: [&[u8]; 3] = [b"+O", b"K\r", b"\n+PO"];
+    /// let mut src = ChainedCursor::new(&chunks);
+    ///
+    /// assert_eq!(src.line().unwrap(), &b"+OK"[..]);
+    /// assert_eq!(src.line(), Err(CursorError::Unterminated(3)));
+    /// ```
+    pub fn line(&mut self) -> Result<Cow<'a, [u8]>, CursorError> {
+        let len = self.line_len()?;
+        let line = self.get(self.pos, len)?;
+        self.pos += len + 2;
+        Ok(line)
+    }
+
+    /// Read an ASCII-encoded, `\r\n`-terminated size, advancing the position
+    /// just past the `\n`.
+    ///
+    /// # Errors
+    ///
+    /// The same errors as [`size`](crate::size) are returned.
+    pub fn size(&mut self) -> Result<u64, CursorError> {
+        let line = self.line()?;
+        crate::parse_size(&line)
+    }
+
+    /// Read an ASCII-encoded, `\r\n`-terminated integer, advancing the
+    /// position just past the `\n`.
+    ///
+    /// # Errors
+    ///
+    /// The same errors as [`number`](crate::number) are returned.
+    pub fn number<T>(&mut self) -> Result<T, CursorError>
+    where
+        T: atoi::FromRadix10SignedChecked,
+    {
+        let line = self.line()?;
+        crate::parse_number(&line)
+    }
+
+    /// Read an ASCII-encoded, `\r\n`-terminated `i64`, advancing the position
+    /// just past the `\n`.
+    ///
+    /// # Errors
+    ///
+    /// The same errors as [`integer`](crate::integer) are returned.
+    pub fn integer(&mut self) -> Result<i64, CursorError> {
+        self.number()
+    }
+
+    /// Read a size line and then that many bytes followed by `\r\n`, advancing
+    /// the position just past the final `\n`.
+    ///
+    /// # Errors
+    ///
+    /// The same errors as [`bulk`](crate::bulk) are returned, and the cursor
+    /// isn't advanced if there isn't enough data yet.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cursor::chain::ChainedCursor;
+    /// # use cursor::CursorError;
+    /// let chunks: [&[u8]; 2] = [b"100000000000\r\nab", b"c"];
+    /// let mut src = ChainedCursor::new(&chunks);
+    ///
+    /// assert_eq!(src.bulk(), Err(CursorError::Incomplete));
+    /// assert_eq!(src.position(), 0);
+    /// ```
+    pub fn bulk(&mut self) -> Result<Cow<'a, [u8]>, CursorError> {
+        let start = self.pos;
+        let result = (|| {
+            let len = self.size()?;
+            let len = usize::try_from(len).map_err(|_| CursorError::SizeOverflow)?;
+            let payload = self.slice(len)?;
+            if *self.slice(2)? != *b"\r\n" {
+                return Err(CursorError::MissingCrlf);
+            }
+            Ok(payload)
+        })();
+
+        if matches!(&result, Err(err) if err.not_enough_data()) {
+            self.pos = start;
+        }
+        result
+    }
+
+    /// Returns the length of the line at the position, without the `\r\n`.
+    fn line_len(&self) -> Result<usize, CursorError> {
+        let mut len = 0;
+        let mut after_cr = false;
+        for piece in self.pieces() {
+            if after_cr && piece[0] == b'\n' {
+                return Ok(len - 1);
+            }
+            if let Some(i) = crate::find(piece, b"\r\n") {
+                return Ok(len + i);
+            }
+            after_cr = piece.ends_with(b"\r");
+            len += piece.len();
+        }
+        Err(CursorError::Unterminated(len))
+    }
+
+    /// Returns the `len` bytes starting at `pos`, only copying if they span
+    /// more than one chunk.
+    fn get(&self, pos: usize, len: usize) -> Result<Cow<'a, [u8]>, CursorError> {
+        let mut pieces = Pieces::new(self.chunks, pos);
+        match pieces.next() {
+            Some(piece) if piece.len() >= len => Ok(Cow::Borrowed(&piece[..len])),
+            Some(piece) => {
+                // Check that the bytes have arrived before allocating for them
+                if Pieces::new(self.chunks, pos)
+                    .map(<[u8]>::len)
+                    .sum::<usize>()
+                    < len
+                {
+                    return Err(CursorError::Incomplete);
+                }
+                let mut bytes = Vec::with_capacity(len);
+                bytes.extend_from_slice(piece);
+                for piece in pieces {
+                    let needed = len - bytes.len();
+                    bytes.extend_from_slice(&piece[..needed.min(piece.len())]);
+                    if bytes.len() == len {
+                        return Ok(Cow::Owned(bytes));
+                    }
+                }
+                Err(CursorError::Incomplete)
+            }
+            None if len == 0 => Ok(Cow::Borrowed(&[])),
+            None => Err(CursorError::Incomplete),
+        }
+    }
+
+    /// Returns the non-empty parts of the chunks after the position.
+    fn pieces(&self) -> Pieces<'a> {
+        Pieces::new(self.chunks, self.pos)
+    }
+}
+
+/// An iterator over the non-empty parts of some chunks after a position.
+struct Pieces<'a> {
+    chunks: core::slice::Iter<'a, &'a [u8]>,
+    skip: usize,
+}
+
+impl<'a> Pieces<'a> {
+    fn new(chunks: &'a [&'a [u8]], pos: usize) -> Self {
+        Pieces {
+            chunks: chunks.iter(),
+            skip: pos,
+        }
+    }
+}
+
+impl<'a> Iterator for Pieces<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let chunk = *self.chunks.next()?;
+            if self.skip >= chunk.len() {
+                self.skip -= chunk.len();
+                continue;
+            }
+            let piece = &chunk[self.skip..];
+            self.skip = 0;
+            return Some(piece);
+        }
+    }
+}
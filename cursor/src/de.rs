@@ -0,0 +1,399 @@
+//! A [`serde`] deserializer for line-oriented messages, enabled with the
+//! `serde` feature.
+//!
+//! Values are read from consecutive `\r\n`-terminated lines using the parsing
+//! functions in this crate:
+//!
+//! * Integers are read with [`number`], and floats with [`double`].
+//! * Booleans are read with [`boolean`].
+//! * Strings and chars are read with [`str_line`], borrowing from the buffer.
+//! * Byte blobs are read with [`bulk`], borrowing from the buffer.
+//! * Options are `None` for an empty line, and otherwise `Some` of the value.
+//! * Units are an empty line.
+//! * Sequences and maps start with a [`size`] line giving how many elements
+//!   or entries follow. Tuples and structs have no size line, since their
+//!   length is known.
+//! * Enums are the variant name on a line, followed by its fields, if any.
+//! * Ignored values, like [`IgnoredAny`](de::IgnoredAny), skip a single line.
+//!
+//! Since the format isn't self-describing, `deserialize_any` isn't supported.
+//!
+//! ```
+//! # use serde::de::IgnoredAny;
+//! let buf = b"1\r\nunused\r\n2\r\n";
+//! let (first, _, second): (u8, IgnoredAny, u8) = cursor::from_slice(buf).unwrap();
+//!
+//! assert_eq!((first, second), (1, 2));
+//! ```
+//!
+//! # Examples
+//! ```
+//! # use serde::Deserialize;
+//! #[derive(Deserialize, Debug, PartialEq)]
+//! struct Message<'a> {
+//!     id: u32,
+//!     name: &'a str,
+//!     payload: &'a [u8],
+//!     tags: Vec<String>,
+//! }
+//!
+//! let buf = b"42\r\nalice\r\n5\r\nhello\r\n2\r\nred\r\nblue\r\n";
+//! let message: Message = cursor::from_slice(buf).unwrap();
+//!
+//! assert_eq!(
+//!     message,
+//!     Message {
+//!         id: 42,
+//!         name: "alice",
+//!         payload: b"hello",
+//!         tags: vec!["red".to_string(), "blue".to_string()],
+//!     },
+//! );
+//! ```
+use crate::{
+    boolean, bulk, double, line, number, peek_line, size, skip_line, str_line, Cursor, CursorError,
+};
+use serde::de::{self, DeserializeSeed, IntoDeserializer, Visitor};
+use std::fmt;
+
+/// Error type for deserializing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// Reading from the cursor failed.
+    Cursor(CursorError),
+    /// There were bytes left over after deserializing a value with [`from_slice`].
+    Trailing(usize),
+    /// A message from the type being deserialized.
+    Custom(String),
+}
+
+impl Error {
+    /// See [`CursorError::not_enough_data`].
+    pub fn not_enough_data(&self) -> bool {
+        matches!(self, Self::Cursor(err) if err.not_enough_data())
+    }
+}
+
+impl From<CursorError> for Error {
+    fn from(err: CursorError) -> Self {
+        Self::Cursor(err)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cursor(err) => err.fmt(f),
+            Self::Trailing(len) => write!(f, "{len} trailing bytes"),
+            Self::Custom(msg) => msg.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Cursor(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+/// Deserialize a `T` from the whole of `buf`.
+///
+/// # Errors
+///
+/// If reading any field fails, the error is returned. If there are any bytes
+/// left after `T` is deserialized, `Error::Trailing` is returned.
+pub fn from_slice<'de, T: de::Deserialize<'de>>(buf: &'de [u8]) -> Result<T, Error> {
+    let mut src = Cursor::new(buf);
+    let value = from_cursor(&mut src)?;
+    match crate::remaining(&src) {
+        0 => Ok(value),
+        len => Err(Error::Trailing(len)),
+    }
+}
+
+/// Deserialize a `T` from the front of a cursor, advancing the position just
+/// past it.
+///
+/// # Errors
+///
+/// If the value isn't complete yet, an error where [`Error::not_enough_data`]
+/// is `true` is returned and the cursor is not advanced.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// let mut src: Cursor<&[u8]> = Cursor::new(b"1\r\n2\r\n3\r\n");
+///
+/// let pair: (u8, u8) = cursor::from_cursor(&mut src).unwrap();
+/// assert_eq!(pair, (1, 2));
+///
+/// let err = cursor::from_cursor::<(u8, u8)>(&mut src).unwrap_err();
+/// assert!(err.not_enough_data());
+/// assert_eq!(src.position(), 6);
+/// ```
+pub fn from_cursor<'de, T: de::Deserialize<'de>>(src: &mut Cursor<&'de [u8]>) -> Result<T, Error> {
+    let pos = src.position();
+    let result = T::deserialize(&mut Deserializer::new(src));
+    if matches!(&result, Err(err) if err.not_enough_data()) {
+        src.set_position(pos);
+    }
+    result
+}
+
+/// A [`serde::Deserializer`] reading from a cursor.
+///
+/// See the [module documentation](self) for the format.
+#[derive(Debug)]
+pub struct Deserializer<'a, 'de> {
+    src: &'a mut Cursor<&'de [u8]>,
+}
+
+impl<'a, 'de> Deserializer<'a, 'de> {
+    /// Creates a new [`Deserializer`] reading from `src`.
+    pub fn new(src: &'a mut Cursor<&'de [u8]>) -> Self {
+        Deserializer { src }
+    }
+}
+
+macro_rules! deserialize_number {
+    ($($method:ident => $visit:ident,)*) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            visitor.$visit(number(self.src)?)
+        }
+    )*};
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'_, 'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+        Err(de::Error::custom("deserialize_any is not supported"))
+    }
+
+    deserialize_number! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_bool(boolean(self.src)?)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_f32(double(self.src)? as f32)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_f64(double(self.src)?)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let mut chars = str_line(self.src)?.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => visitor.visit_char(c),
+            _ => Err(de::Error::custom("expected a single character")),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_borrowed_str(str_line(self.src)?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_borrowed_bytes(bulk(self.src)?)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if peek_line(self.src)?.is_empty() {
+            skip_line(self.src)?;
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if line(self.src)?.is_empty() {
+            visitor.visit_unit()
+        } else {
+            Err(de::Error::custom("expected an empty line"))
+        }
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let len = size(self.src)?;
+        visitor.visit_seq(Elements { de: self, len })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(Elements {
+            de: self,
+            len: len as u64,
+        })
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let len = size(self.src)?;
+        visitor.visit_map(Elements { de: self, len })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_tuple(fields.len(), visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        skip_line(self.src)?;
+        visitor.visit_unit()
+    }
+}
+
+/// Access to a known number of elements of a sequence, or entries of a map.
+struct Elements<'r, 'a, 'de> {
+    de: &'r mut Deserializer<'a, 'de>,
+    len: u64,
+}
+
+impl<'de> de::SeqAccess<'de> for Elements<'_, '_, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.len -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        usize::try_from(self.len).ok()
+    }
+}
+
+impl<'de> de::MapAccess<'de> for Elements<'_, '_, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        self.len -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        usize::try_from(self.len).ok()
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for &mut Deserializer<'_, 'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error> {
+        let name = str_line(self.src)?;
+        let variant = seed.deserialize(IntoDeserializer::<'_, Error>::into_deserializer(name))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for &mut Deserializer<'_, 'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_tuple(self, len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_tuple(self, fields.len(), visitor)
+    }
+}
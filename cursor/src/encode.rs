@@ -0,0 +1,206 @@
+//! Writing the wire format read by this crate.
+//!
+//! Each function here is the counterpart of a parsing function, and writes
+//! into any [`Write`], including a `Vec<u8>`.
+use crate::frame::Frame;
+use std::io::{self, Write};
+
+/// Write a `\r\n`-terminated line, the counterpart of [`line`](crate::line).
+///
+/// The caller is responsible for making sure that `line` doesn't contain `\r\n`.
+///
+/// # Examples
+/// ```
+/// # use cursor::encode::put_line;
+/// let mut dst = Vec::new();
+/// put_line(&mut dst, b"Hello, world!").unwrap();
+///
+/// assert_eq!(dst, b"Hello, world!\r\n");
+/// ```
+pub fn put_line<W: Write>(dst: &mut W, line: &[u8]) -> io::Result<()> {
+    dst.write_all(line)?;
+    dst.write_all(b"\r\n")
+}
+
+/// Write an ASCII-encoded, `\r\n`-terminated 64-bit signed integer, the
+/// counterpart of [`integer`](crate::integer).
+///
+/// # Examples
+/// ```
+/// # use cursor::encode::put_integer;
+/// let mut dst = Vec::new();
+/// put_integer(&mut dst, -100).unwrap();
+///
+/// assert_eq!(dst, b"-100\r\n");
+/// ```
+pub fn put_integer<W: Write>(dst: &mut W, n: i64) -> io::Result<()> {
+    write!(dst, "{n}\r\n")
+}
+
+/// Write an ASCII-encoded, `\r\n`-terminated decimal size, the counterpart
+/// of [`size`](crate::size).
+///
+/// # Examples
+/// ```
+/// # use cursor::encode::put_size;
+/// let mut dst = Vec::new();
+/// put_size(&mut dst, 100).unwrap();
+///
+/// assert_eq!(dst, b"100\r\n");
+/// ```
+pub fn put_size<W: Write>(dst: &mut W, n: u64) -> io::Result<()> {
+    write!(dst, "{n}\r\n")
+}
+
+/// Write an ASCII-encoded, `\r\n`-terminated 64-bit float, the counterpart
+/// of [`double`](crate::double).
+///
+/// # Examples
+/// ```
+/// # use cursor::encode::put_double;
+/// let mut dst = Vec::new();
+/// put_double(&mut dst, 1.5).unwrap();
+/// put_double(&mut dst, f64::NEG_INFINITY).unwrap();
+/// put_double(&mut dst, f64::NAN).unwrap();
+///
+/// assert_eq!(dst, b"1.5\r\n-inf\r\nnan\r\n");
+/// ```
+pub fn put_double<W: Write>(dst: &mut W, n: f64) -> io::Result<()> {
+    if n.is_nan() {
+        dst.write_all(b"nan\r\n")
+    } else {
+        write!(dst, "{n}\r\n")
+    }
+}
+
+/// Write a size-prefixed payload followed by `\r\n`, the counterpart of
+/// [`bulk`](crate::bulk).
+///
+/// # Examples
+/// ```
+/// # use cursor::encode::put_bulk;
+/// let mut dst = Vec::new();
+/// put_bulk(&mut dst, b"Hello").unwrap();
+///
+/// assert_eq!(dst, b"5\r\nHello\r\n");
+/// ```
+pub fn put_bulk<W: Write>(dst: &mut W, payload: &[u8]) -> io::Result<()> {
+    put_size(dst, payload.len() as u64)?;
+    put_line(dst, payload)
+}
+
+/// Write a netstring, the counterpart of [`netstring`](crate::netstring).
+///
+/// # Examples
+/// ```
+/// # use cursor::encode::put_netstring;
+/// let mut dst = Vec::new();
+/// put_netstring(&mut dst, b"hello").unwrap();
+///
+/// assert_eq!(dst, b"5:hello,");
+/// ```
+pub fn put_netstring<W: Write>(dst: &mut W, payload: &[u8]) -> io::Result<()> {
+    write!(dst, "{}:", payload.len())?;
+    dst.write_all(payload)?;
+    dst.write_all(b",")
+}
+
+/// Write a payload prefixed by its length as a big-endian `u32`, the
+/// counterpart of [`length_prefixed`](crate::length_prefixed).
+///
+/// # Errors
+///
+/// If `payload` is longer than `u32::MAX` bytes, an error of kind
+/// [`io::ErrorKind::InvalidInput`] is returned and nothing is written.
+///
+/// # Examples
+/// ```
+/// # use cursor::encode::put_length_prefixed;
+/// let mut dst = Vec::new();
+/// put_length_prefixed(&mut dst, b"hello").unwrap();
+///
+/// assert_eq!(dst, b"\0\0\0\x05hello");
+/// ```
+pub fn put_length_prefixed<W: Write>(dst: &mut W, payload: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(payload.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "payload too long"))?;
+    dst.write_all(&len.to_be_bytes())?;
+    dst.write_all(payload)
+}
+
+/// Write an entire [`Frame`], the counterpart of [`Frame::parse`].
+///
+/// [`Frame::Null`] is written as the RESP2 null bulk string, `$-1\r\n`.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::encode::put_frame;
+/// # use cursor::frame::Frame;
+/// let frame = Frame::Array(vec![Frame::Integer(1), Frame::Bulk(b"hello".to_vec())]);
+///
+/// let mut dst = Vec::new();
+/// put_frame(&mut dst, &frame).unwrap();
+/// assert_eq!(dst, b"*2\r\n:1\r\n$5\r\nhello\r\n");
+///
+/// // Parsing gives back the same frame
+/// assert_eq!(Frame::parse(&mut Cursor::new(&dst[..])), Ok(frame));
+/// ```
+pub fn put_frame<W: Write>(dst: &mut W, frame: &Frame) -> io::Result<()> {
+    match frame {
+        Frame::Simple(s) => {
+            dst.write_all(b"+")?;
+            put_line(dst, s.as_bytes())
+        }
+        Frame::Error(s) => {
+            dst.write_all(b"-")?;
+            put_line(dst, s.as_bytes())
+        }
+        Frame::Integer(n) => {
+            dst.write_all(b":")?;
+            put_integer(dst, *n)
+        }
+        Frame::Bulk(payload) => {
+            dst.write_all(b"$")?;
+            put_bulk(dst, payload)
+        }
+        Frame::Null => dst.write_all(b"$-1\r\n"),
+        Frame::Array(frames) => put_aggregate(dst, b'*', frames),
+        Frame::Map(pairs) => {
+            dst.write_all(b"%")?;
+            put_size(dst, pairs.len() as u64)?;
+            for (key, value) in pairs {
+                put_frame(dst, key)?;
+                put_frame(dst, value)?;
+            }
+            Ok(())
+        }
+        Frame::Set(frames) => put_aggregate(dst, b'~', frames),
+        Frame::Double(n) => {
+            dst.write_all(b",")?;
+            put_double(dst, *n)
+        }
+        Frame::Boolean(b) => dst.write_all(if *b { b"#t\r\n" } else { b"#f\r\n" }),
+        Frame::BigNumber(digits) => {
+            dst.write_all(b"(")?;
+            put_line(dst, digits.as_bytes())
+        }
+        Frame::Verbatim { format, text } => {
+            dst.write_all(b"=")?;
+            put_size(dst, text.len() as u64 + 4)?;
+            dst.write_all(format)?;
+            dst.write_all(b":")?;
+            put_line(dst, text)
+        }
+        Frame::Push(frames) => put_aggregate(dst, b'>', frames),
+    }
+}
+
+fn put_aggregate<W: Write>(dst: &mut W, kind: u8, frames: &[Frame]) -> io::Result<()> {
+    dst.write_all(&[kind])?;
+    put_size(dst, frames.len() as u64)?;
+    for frame in frames {
+        put_frame(dst, frame)?;
+    }
+    Ok(())
+}
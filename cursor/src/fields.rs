@@ -0,0 +1,188 @@
+//! Splitting a line into whitespace-separated fields.
+
+use crate::{line, scan_quoted, Cursor, CursorError};
+use alloc::borrow::Cow;
+
+/// Returns an iterator over the fields of `line`, which are separated by runs
+/// of spaces and tabs.
+///
+/// # Examples
+/// ```
+/// # use cursor::fields;
+/// let fields: Vec<&[u8]> = fields(b"SET  key\t42").collect();
+/// assert_eq!(fields, [&b"SET"[..], b"key", b"42"]);
+/// ```
+pub fn fields(line: &[u8]) -> Fields<'_> {
+    Fields { rest: line }
+}
+
+/// Read a `\r\n`-terminated line from a cursor, advancing the position just
+/// past the `\n`, and return an iterator over its fields.
+///
+/// # Errors
+///
+/// The same errors as [`line`] are returned.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{words, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("SET key 42 EX 100\r\n".as_bytes());
+///
+/// let mut words = words(&mut src).unwrap();
+/// assert_eq!(words.next_str(), Ok("SET"));
+/// assert_eq!(words.next_bytes(), Ok("key".as_bytes()));
+/// assert_eq!(words.next_int(), Ok(42));
+/// assert_eq!(words.next_str(), Ok("EX"));
+/// assert_eq!(words.next_number::<u32>(), Ok(100));
+/// assert_eq!(words.next_str(), Err(CursorError::MissingField));
+/// ```
+pub fn words<'buf>(
+    src: &mut Cursor<&'buf (impl AsRef<[u8]> + ?Sized)>,
+) -> Result<Fields<'buf>, CursorError> {
+    line(src).map(fields)
+}
+
+/// An iterator over the whitespace-separated fields of a line.
+///
+/// This is returned by [`fields`] and [`words`].
+#[derive(Debug, Clone)]
+pub struct Fields<'buf> {
+    rest: &'buf [u8],
+}
+
+impl<'buf> Fields<'buf> {
+    /// Returns the next field.
+    ///
+    /// # Errors
+    ///
+    /// If there are no fields left, `CursorError::MissingField` is returned.
+    pub fn next_bytes(&mut self) -> Result<&'buf [u8], CursorError> {
+        self.next().ok_or(CursorError::MissingField)
+    }
+
+    /// Returns the next field as a UTF-8 string.
+    ///
+    /// # Errors
+    ///
+    /// If there are no fields left, `CursorError::MissingField` is returned.
+    /// If the field isn't valid UTF-8, `CursorError::Utf8` is returned with the
+    /// offset of the first invalid byte from the start of the field.
+    pub fn next_str(&mut self) -> Result<&'buf str, CursorError> {
+        let field = self.next_bytes()?;
+        core::str::from_utf8(field).map_err(|err| CursorError::Utf8(err.valid_up_to()))
+    }
+
+    /// Returns the next field as an ASCII-encoded integer.
+    ///
+    /// # Errors
+    ///
+    /// If there are no fields left, `CursorError::MissingField` is returned.
+    /// If the field isn't all digits, `CursorError::InvalidDigit` is returned,
+    /// and if the digits don't fit in a `T`, `CursorError::Overflow` is
+    /// returned.
+    pub fn next_number<T>(&mut self) -> Result<T, CursorError>
+    where
+        T: atoi::FromRadix10SignedChecked,
+    {
+        let field = self.next_bytes()?;
+        match atoi::FromRadix10SignedChecked::from_radix_10_signed_checked(field) {
+            (Some(n), used) if used == field.len() => Ok(n),
+            (None, used) if used == field.len() => Err(CursorError::Overflow),
+            _ => Err(CursorError::InvalidDigit),
+        }
+    }
+
+    /// Returns the next field, which may be a double-quoted string with
+    /// spaces and escapes in it.
+    ///
+    /// A quoted field is unescaped like [`quoted_string`](crate::quoted_string),
+    /// and a field without quotes is returned as is.
+    ///
+    /// # Errors
+    ///
+    /// If there are no fields left, `CursorError::MissingField` is returned.
+    /// If a quoted field has no closing `"`, `CursorError::UnterminatedQuote`
+    /// is returned, and if it has an escape that isn't recognized,
+    /// `CursorError::BadEscape` is returned. If the closing `"` is followed by
+    /// something other than whitespace, `CursorError::Mismatch` is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cursor::fields;
+    /// let mut fields = fields(br#"SET "my key" "a\"b""#);
+    /// assert_eq!(fields.next_token().unwrap(), &b"SET"[..]);
+    /// assert_eq!(fields.next_token().unwrap(), &b"my key"[..]);
+    /// assert_eq!(fields.next_token().unwrap(), &b"a\"b"[..]);
+    /// ```
+    pub fn next_token(&mut self) -> Result<Cow<'buf, [u8]>, CursorError> {
+        let rest = trim_start(self.rest);
+        if !rest.starts_with(b"\"") {
+            return self.next_bytes().map(Cow::Borrowed);
+        }
+
+        let (token, len) = scan_quoted(rest).map_err(|err| match err {
+            CursorError::Incomplete => CursorError::UnterminatedQuote,
+            err => err,
+        })?;
+        if rest.get(len).is_some_and(|&b| !is_space(b)) {
+            return Err(CursorError::Mismatch);
+        }
+        self.rest = &rest[len..];
+        Ok(token)
+    }
+
+    /// Returns the next field as an ASCII-encoded `i64`.
+    ///
+    /// # Errors
+    ///
+    /// The same errors as [`Fields::next_number`] are returned.
+    pub fn next_int(&mut self) -> Result<i64, CursorError> {
+        self.next_number()
+    }
+
+    /// Returns everything after the fields yielded so far, without leading
+    /// whitespace.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cursor::fields;
+    /// let mut fields = fields(b"ECHO hello world");
+    /// assert_eq!(fields.next_str(), Ok("ECHO"));
+    /// assert_eq!(fields.rest(), b"hello world");
+    /// ```
+    pub fn rest(&self) -> &'buf [u8] {
+        trim_start(self.rest)
+    }
+}
+
+impl<'buf> Iterator for Fields<'buf> {
+    type Item = &'buf [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = trim_start(self.rest);
+        if rest.is_empty() {
+            self.rest = rest;
+            return None;
+        }
+
+        let len = rest.iter().position(|&b| is_space(b)).unwrap_or(rest.len());
+        let (field, rest) = rest.split_at(len);
+        self.rest = rest;
+        Some(field)
+    }
+}
+
+impl core::iter::FusedIterator for Fields<'_> {}
+
+fn is_space(b: u8) -> bool {
+    b == b' ' || b == b'\t'
+}
+
+fn trim_start(bytes: &[u8]) -> &[u8] {
+    let start = bytes
+        .iter()
+        .position(|&b| !is_space(b))
+        .unwrap_or(bytes.len());
+    &bytes[start..]
+}
@@ -0,0 +1,585 @@
+//! Parsing for [RESP](https://redis.io/docs/reference/protocol-spec/) frames,
+//! including the RESP3 extensions.
+use crate::{
+    bulk, byte, double, find, integer, line, parse_number, peek_line, size, skip_line, str_line,
+    with_rollback, Cursor, CursorError,
+};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// A RESP2 or RESP3 frame.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Frame {
+    /// A simple string, like `+OK\r\n`.
+    Simple(String),
+    /// An error, like `-ERR unknown command\r\n`.
+    Error(String),
+    /// An integer, like `:1000\r\n`.
+    Integer(i64),
+    /// A bulk string, like `$5\r\nhello\r\n`.
+    Bulk(Vec<u8>),
+    /// A null, either the RESP2 `$-1\r\n` or `*-1\r\n`, or the RESP3 `_\r\n`.
+    Null,
+    /// An array of frames, like `*2\r\n:1\r\n:2\r\n`.
+    Array(Vec<Frame>),
+    /// A map of key-value pairs in order, like `%1\r\n+key\r\n:1\r\n`.
+    Map(Vec<(Frame, Frame)>),
+    /// A set of frames, like `~2\r\n:1\r\n:2\r\n`.
+    Set(Vec<Frame>),
+    /// A double, like `,3.14\r\n`.
+    Double(f64),
+    /// A boolean, `#t\r\n` or `#f\r\n`.
+    Boolean(bool),
+    /// An arbitrarily large integer as its ASCII digits, like
+    /// `(3492890328409238509324850943850943825024385\r\n`.
+    BigNumber(String),
+    /// A verbatim string with a 3-byte format, like `=9\r\ntxt:hello\r\n`.
+    Verbatim {
+        /// The format of the text, like `txt` or `mkd`.
+        format: [u8; 3],
+        /// The text itself.
+        text: Vec<u8>,
+    },
+    /// Out-of-band data pushed by a server, like `>2\r\n+message\r\n+hi\r\n`.
+    Push(Vec<Frame>),
+}
+
+impl Frame {
+    /// The maximum nesting of aggregate frames allowed by [`Frame::check`]
+    /// and [`Frame::parse`].
+    pub const DEFAULT_MAX_DEPTH: usize = 32;
+
+    /// Checks that an entire frame can be read from a cursor, advancing the
+    /// position just past the end of the frame.
+    ///
+    /// This is cheaper than [`Frame::parse`] since it doesn't allocate, so it
+    /// can be used to find out if a full frame has been buffered yet.
+    ///
+    /// # Errors
+    ///
+    /// If the frame isn't complete yet, an error where [`CursorError::not_enough_data`]
+    /// is `true` is returned and the cursor is not advanced.
+    ///
+    /// If the frame is malformed, the corresponding error is returned and the
+    /// cursor is left just past the bytes that were read. Aggregates nested
+    /// more than [`Frame::DEFAULT_MAX_DEPTH`] deep are rejected with
+    /// `CursorError::TooDeep`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::io::Cursor;
+    /// # use cursor::frame::Frame;
+    /// let mut src: Cursor<&[u8]> = Cursor::new("*2\r\n+OK\r\n$5\r\nhel".as_bytes());
+    ///
+    /// assert!(Frame::check(&mut src).unwrap_err().not_enough_data());
+    /// assert_eq!(src.position(), 0);
+    ///
+    /// let mut src: Cursor<&[u8]> = Cursor::new("*2\r\n+OK\r\n$5\r\nhello\r\n".as_bytes());
+    ///
+    /// assert_eq!(Frame::check(&mut src), Ok(()));
+    /// assert_eq!(src.position(), 20);
+    /// ```
+    /// Checking a bulk string whose size can't fit in memory:
+    /// ```
+    /// # use std::io::Cursor;
+    /// # use cursor::frame::Frame;
+    /// # use cursor::CursorError;
+    /// let mut src: Cursor<&[u8]> = Cursor::new("$18446744073709551615\r\nabc".as_bytes());
+    ///
+    /// assert_eq!(Frame::check(&mut src), Err(CursorError::SizeOverflow));
+    /// ```
+    pub fn check(src: &mut Cursor<&[u8]>) -> Result<(), CursorError> {
+        Self::check_with_max_depth(src, Self::DEFAULT_MAX_DEPTH)
+    }
+
+    /// Like [`Frame::check`], but allowing aggregates to be nested up to
+    /// `max_depth` deep.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::io::Cursor;
+    /// # use cursor::frame::Frame;
+    /// # use cursor::CursorError;
+    /// let mut src: Cursor<&[u8]> = Cursor::new("*1\r\n*1\r\n*0\r\n".as_bytes());
+    ///
+    /// let result = Frame::check_with_max_depth(&mut src, 2);
+    /// assert_eq!(result, Err(CursorError::TooDeep));
+    /// ```
+    pub fn check_with_max_depth(
+        src: &mut Cursor<&[u8]>,
+        max_depth: usize,
+    ) -> Result<(), CursorError> {
+        with_rollback(src, |src| check(src, max_depth))
+    }
+
+    /// Parses an entire frame from a cursor, advancing the position just past
+    /// the end of the frame.
+    ///
+    /// # Errors
+    ///
+    /// This method fails in the same ways as [`Frame::check`], and also returns
+    /// `CursorError::Utf8` if a simple string or error isn't valid UTF-8.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::io::Cursor;
+    /// # use cursor::frame::Frame;
+    /// let mut src: Cursor<&[u8]> = Cursor::new("*3\r\n:1\r\n$-1\r\n$5\r\nhello\r\n".as_bytes());
+    ///
+    /// let frame = Frame::parse(&mut src);
+    /// assert_eq!(
+    ///     frame,
+    ///     Ok(Frame::Array(vec![
+    ///         Frame::Integer(1),
+    ///         Frame::Null,
+    ///         Frame::Bulk(b"hello".to_vec()),
+    ///     ])),
+    /// );
+    /// ```
+    /// Parsing RESP3 frames:
+    /// ```
+    /// # use std::io::Cursor;
+    /// # use cursor::frame::Frame;
+    /// let mut src: Cursor<&[u8]> = Cursor::new("%2\r\n+a\r\n#t\r\n+b\r\n,1.5\r\n".as_bytes());
+    ///
+    /// let frame = Frame::parse(&mut src);
+    /// assert_eq!(
+    ///     frame,
+    ///     Ok(Frame::Map(vec![
+    ///         (Frame::Simple("a".to_string()), Frame::Boolean(true)),
+    ///         (Frame::Simple("b".to_string()), Frame::Double(1.5)),
+    ///     ])),
+    /// );
+    /// ```
+    pub fn parse(src: &mut Cursor<&[u8]>) -> Result<Frame, CursorError> {
+        Self::parse_with_max_depth(src, Self::DEFAULT_MAX_DEPTH)
+    }
+
+    /// Like [`Frame::parse`], but allowing aggregates to be nested up to
+    /// `max_depth` deep.
+    pub fn parse_with_max_depth(
+        src: &mut Cursor<&[u8]>,
+        max_depth: usize,
+    ) -> Result<Frame, CursorError> {
+        with_rollback(src, |src| parse(src, max_depth))
+    }
+
+    /// Returns the value of an integer or big number frame as an `i128`.
+    ///
+    /// # Errors
+    ///
+    /// If a big number doesn't fit in an `i128`, `CursorError::Overflow` is
+    /// returned. If the frame isn't an integer or big number,
+    /// `CursorError::Mismatch` is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::io::Cursor;
+    /// # use cursor::frame::Frame;
+    /// # use cursor::CursorError;
+    /// let mut src: Cursor<&[u8]> = Cursor::new("(18446744073709551616\r\n".as_bytes());
+    /// let frame = Frame::parse(&mut src).unwrap();
+    /// assert_eq!(frame.to_i128(), Ok(u64::MAX as i128 + 1));
+    ///
+    /// let frame = Frame::BigNumber("1".repeat(40));
+    /// assert_eq!(frame.to_i128(), Err(CursorError::Overflow));
+    /// ```
+    pub fn to_i128(&self) -> Result<i128, CursorError> {
+        match self {
+            Frame::Integer(n) => Ok(i128::from(*n)),
+            Frame::BigNumber(digits) => parse_number(digits.as_bytes()),
+            _ => Err(CursorError::Mismatch),
+        }
+    }
+}
+
+impl From<i128> for Frame {
+    /// Creates an integer frame if `n` fits in an `i64`, and a big number
+    /// frame otherwise.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cursor::frame::Frame;
+    /// assert_eq!(Frame::from(42i128), Frame::Integer(42));
+    /// assert_eq!(
+    ///     Frame::from(i128::MAX),
+    ///     Frame::BigNumber("170141183460469231731687303715884105727".to_string()),
+    /// );
+    /// ```
+    fn from(n: i128) -> Self {
+        match i64::try_from(n) {
+            Ok(n) => Frame::Integer(n),
+            Err(_) => Frame::BigNumber(n.to_string()),
+        }
+    }
+}
+
+/// An incremental frame checker that remembers how much of a frame it has
+/// already checked, so that it doesn't start over when more data arrives.
+///
+/// [`Frame::check`] has to rescan a partial frame from the start every time
+/// it's called, which is quadratic for large frames arriving in small pieces.
+/// A [`FrameParser`] instead picks up where it left off: after a bulk string's
+/// size line, for example, it only waits for the payload bytes to arrive.
+///
+/// The buffer passed to each call must start at the beginning of the frame,
+/// and must contain at least the bytes passed to the previous call. This is
+/// exactly what `ReadBuf::buf` from the `readbuf` crate provides as long as
+/// nothing is consumed until the frame is complete, even if the buffer is
+/// compacted in between.
+///
+/// # Examples
+/// ```
+/// # use cursor::frame::{Frame, FrameParser};
+/// let mut parser = FrameParser::new();
+///
+/// // Only part of the frame has arrived
+/// let mut buf = b"*2\r\n$5\r\nhel".to_vec();
+/// assert_eq!(parser.check(&buf), Ok(None));
+///
+/// // The rest arrives, and checking resumes with the payload
+/// buf.extend_from_slice(b"lo\r\n:1\r\n+next");
+/// assert_eq!(parser.check(&buf), Ok(Some(19)));
+///
+/// // The parser is ready for the next frame
+/// assert_eq!(parser.check(&buf[19..]), Ok(None));
+/// ```
+#[derive(Debug, Clone)]
+pub struct FrameParser {
+    max_depth: usize,
+    /// How many bytes at the front of the frame have been checked.
+    checked: usize,
+    /// How far the line starting at `checked` has been scanned without
+    /// finding a `\r\n`.
+    scanned: usize,
+    /// Where the payload of the bulk or verbatim string at `checked` ends,
+    /// once its size line has been read.
+    payload_end: Option<usize>,
+    /// How many elements are left in each aggregate being checked.
+    remaining: Vec<u64>,
+}
+
+impl FrameParser {
+    /// Creates a new [`FrameParser`] allowing aggregates to be nested up to
+    /// [`Frame::DEFAULT_MAX_DEPTH`] deep.
+    pub fn new() -> Self {
+        Self::with_max_depth(Frame::DEFAULT_MAX_DEPTH)
+    }
+
+    /// Creates a new [`FrameParser`] allowing aggregates to be nested up to
+    /// `max_depth` deep.
+    pub fn with_max_depth(max_depth: usize) -> Self {
+        FrameParser {
+            max_depth,
+            checked: 0,
+            scanned: 0,
+            payload_end: None,
+            remaining: Vec::new(),
+        }
+    }
+
+    /// Returns how many bytes of the current frame have been checked so far.
+    pub fn checked(&self) -> usize {
+        self.checked
+    }
+
+    /// Forgets any progress on the current frame.
+    pub fn reset(&mut self) {
+        self.checked = 0;
+        self.scanned = 0;
+        self.payload_end = None;
+        self.remaining.clear();
+    }
+
+    /// Continues checking the frame at the start of `buf`, returning the length
+    /// of the frame once it's complete, or `None` if more data is needed.
+    ///
+    /// Once a frame is complete, the parser is reset for the next frame.
+    ///
+    /// # Errors
+    ///
+    /// If the frame is malformed, the same error as [`Frame::check`] is returned.
+    /// The parser should be [reset](FrameParser::reset) before being used again.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cursor::frame::FrameParser;
+    /// # use cursor::CursorError;
+    /// let mut parser = FrameParser::new();
+    ///
+    /// let result = parser.check(b"$18446744073709551615\r\nabc");
+    /// assert_eq!(result, Err(CursorError::SizeOverflow));
+    ///
+    /// // An unknown type is rejected before its line ends
+    /// parser.reset();
+    /// assert_eq!(parser.check(b"GET /"), Err(CursorError::FrameType(b'G')));
+    /// ```
+    pub fn check(&mut self, buf: &[u8]) -> Result<Option<usize>, CursorError> {
+        loop {
+            if let Some(end) = self.payload_end {
+                let end = end.checked_add(2).ok_or(CursorError::SizeOverflow)?;
+                if buf.len() < end {
+                    return Ok(None);
+                }
+                // The whole element has arrived, so check it all at once
+                check(&mut Cursor::new(&buf[self.checked..end]), 0)?;
+                self.payload_end = None;
+                self.checked = end;
+            } else {
+                // Reject an unknown type right away, like `Frame::check` does,
+                // instead of waiting for the rest of its line
+                if let Some(&b) = buf.get(self.checked).filter(|&&b| !is_frame_type(b)) {
+                    return Err(CursorError::FrameType(b));
+                }
+                let Some(line_end) = self.scan_line(buf) else {
+                    return Ok(None);
+                };
+                let start = self.checked;
+                let after = line_end + 2;
+                let len = || size(&mut Cursor::new(&buf[start + 1..after]));
+
+                match buf[start] {
+                    b'$' | b'=' if &buf[start + 1..line_end] != b"-1" => {
+                        let end = usize::try_from(len()?)
+                            .ok()
+                            .and_then(|len| after.checked_add(len))
+                            .ok_or(CursorError::SizeOverflow)?;
+                        self.payload_end = Some(end);
+                        continue;
+                    }
+                    b'*' | b'~' | b'>' | b'%' if &buf[start + 1..line_end] != b"-1" => {
+                        let count = match buf[start] {
+                            b'%' => len()?.saturating_mul(2),
+                            _ => len()?,
+                        };
+                        if self.remaining.len() == self.max_depth {
+                            return Err(CursorError::TooDeep);
+                        }
+                        self.checked = after;
+                        if count > 0 {
+                            self.remaining.push(count);
+                            continue;
+                        }
+                    }
+                    _ => {
+                        check(&mut Cursor::new(&buf[start..after]), 0)?;
+                        self.checked = after;
+                    }
+                }
+            }
+
+            // An element was completed, so count it against its aggregates
+            loop {
+                match self.remaining.last_mut() {
+                    None => {
+                        let len = self.checked;
+                        self.reset();
+                        return Ok(Some(len));
+                    }
+                    Some(1) => {
+                        self.remaining.pop();
+                    }
+                    Some(count) => {
+                        *count -= 1;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Continues checking the frame at the start of `buf`, and once it's
+    /// complete, parses it and returns it along with its length.
+    ///
+    /// # Errors
+    ///
+    /// If the frame is malformed, the same error as [`Frame::parse`] is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cursor::frame::{Frame, FrameParser};
+    /// let mut parser = FrameParser::new();
+    ///
+    /// assert_eq!(parser.parse(b"+O"), Ok(None));
+    /// assert_eq!(parser.parse(b"+OK\r\n"), Ok(Some((Frame::Simple("OK".into()), 5))));
+    /// ```
+    pub fn parse(&mut self, buf: &[u8]) -> Result<Option<(Frame, usize)>, CursorError> {
+        let max_depth = self.max_depth;
+        match self.check(buf)? {
+            Some(len) => {
+                let frame = Frame::parse_with_max_depth(&mut Cursor::new(&buf[..len]), max_depth)?;
+                Ok(Some((frame, len)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Finds the `\r\n` ending the line at `checked`, resuming from wherever
+    /// the last search ended.
+    fn scan_line(&mut self, buf: &[u8]) -> Option<usize> {
+        let from = self.scanned.max(self.checked + 1);
+        match buf.get(from..).and_then(|rem| find(rem, b"\r\n")) {
+            Some(index) => {
+                self.scanned = 0;
+                Some(from + index)
+            }
+            None => {
+                // A `\r` at the very end may be followed by a `\n` later
+                self.scanned = buf.len().saturating_sub(1).max(from);
+                None
+            }
+        }
+    }
+}
+
+impl Default for FrameParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns an iterator over the offsets just past each complete frame at the
+/// start of `buf`.
+///
+/// This is for pipelined input, where many frames arrive at once. Each frame
+/// is checked with [`Frame::check`] where the last one ended, so the buffer is
+/// only scanned once overall, and iteration stops at the first frame that's
+/// incomplete or malformed. Nothing is parsed, so to get the frames
+/// themselves, parse them with [`Frame::parse`] directly instead of finding
+/// their boundaries first.
+///
+/// # Examples
+/// ```
+/// # use cursor::frame::find_frame_boundaries;
+/// let buf = b"+OK\r\n*2\r\n$3\r\nGET\r\n$1\r\nk\r\n:1\r\n$5\r\nhel";
+///
+/// let boundaries: Vec<usize> = find_frame_boundaries(buf).collect();
+/// assert_eq!(boundaries, [5, 25, 29]);
+/// ```
+pub fn find_frame_boundaries(buf: &[u8]) -> impl Iterator<Item = usize> + '_ {
+    let mut src = Cursor::new(buf);
+    core::iter::from_fn(move || {
+        Frame::check(&mut src).ok()?;
+        Some(src.position() as usize)
+    })
+    .fuse()
+}
+
+/// Returns `true` if `b` is the first byte of some frame type.
+fn is_frame_type(b: u8) -> bool {
+    matches!(
+        b,
+        b'+' | b'-' | b'_' | b':' | b',' | b'#' | b'(' | b'$' | b'=' | b'*' | b'~' | b'>' | b'%'
+    )
+}
+
+/// Returns the remaining depth inside of an aggregate, or an error if
+/// there's no depth left.
+fn nested(depth: usize) -> Result<usize, CursorError> {
+    depth.checked_sub(1).ok_or(CursorError::TooDeep)
+}
+
+fn check(src: &mut Cursor<&[u8]>, depth: usize) -> Result<(), CursorError> {
+    match byte(src)? {
+        b'+' | b'-' | b'_' => skip_line(src),
+        b':' => integer(src).map(|_| ()),
+        b',' => double(src).map(|_| ()),
+        b'#' => boolean(src).map(|_| ()),
+        b'(' => big_number(src).map(|_| ()),
+        b'$' | b'*' if peek_line(src)? == b"-1" => skip_line(src),
+        b'$' => bulk(src).map(|_| ()),
+        b'=' => verbatim(src).map(|_| ()),
+        b'*' | b'~' | b'>' => {
+            let len = size(src)?;
+            let depth = nested(depth)?;
+            for _ in 0..len {
+                check(src, depth)?;
+            }
+            Ok(())
+        }
+        b'%' => {
+            let len = size(src)?;
+            let depth = nested(depth)?;
+            for _ in 0..len {
+                check(src, depth)?;
+                check(src, depth)?;
+            }
+            Ok(())
+        }
+        b => Err(CursorError::FrameType(b)),
+    }
+}
+
+fn parse(src: &mut Cursor<&[u8]>, depth: usize) -> Result<Frame, CursorError> {
+    match byte(src)? {
+        b'+' => Ok(Frame::Simple(str_line(src)?.to_string())),
+        b'-' => Ok(Frame::Error(str_line(src)?.to_string())),
+        b':' => Ok(Frame::Integer(integer(src)?)),
+        b',' => Ok(Frame::Double(double(src)?)),
+        b'#' => Ok(Frame::Boolean(boolean(src)?)),
+        b'(' => Ok(Frame::BigNumber(big_number(src)?.to_string())),
+        b'_' => {
+            skip_line(src)?;
+            Ok(Frame::Null)
+        }
+        b'$' | b'*' if peek_line(src)? == b"-1" => {
+            skip_line(src)?;
+            Ok(Frame::Null)
+        }
+        b'$' => Ok(Frame::Bulk(bulk(src)?.to_vec())),
+        b'=' => {
+            let (format, text) = verbatim(src)?;
+            Ok(Frame::Verbatim {
+                format,
+                text: text.to_vec(),
+            })
+        }
+        b'*' => Ok(Frame::Array(aggregate(src, depth)?)),
+        b'~' => Ok(Frame::Set(aggregate(src, depth)?)),
+        b'>' => Ok(Frame::Push(aggregate(src, depth)?)),
+        b'%' => {
+            let len = size(src)?;
+            let depth = nested(depth)?;
+            let mut pairs = Vec::new();
+            for _ in 0..len {
+                pairs.push((parse(src, depth)?, parse(src, depth)?));
+            }
+            Ok(Frame::Map(pairs))
+        }
+        b => Err(CursorError::FrameType(b)),
+    }
+}
+
+fn aggregate(src: &mut Cursor<&[u8]>, depth: usize) -> Result<Vec<Frame>, CursorError> {
+    let len = size(src)?;
+    let depth = nested(depth)?;
+    let mut frames = Vec::new();
+    for _ in 0..len {
+        frames.push(parse(src, depth)?);
+    }
+    Ok(frames)
+}
+
+fn boolean(src: &mut Cursor<&[u8]>) -> Result<bool, CursorError> {
+    match line(src)? {
+        b"t" => Ok(true),
+        b"f" => Ok(false),
+        _ => Err(CursorError::Boolean),
+    }
+}
+
+fn big_number<'buf>(src: &mut Cursor<&'buf [u8]>) -> Result<&'buf str, CursorError> {
+    let line = str_line(src)?;
+    let digits = line.strip_prefix(['+', '-']).unwrap_or(line);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(CursorError::InvalidDigit);
+    }
+    Ok(line)
+}
+
+fn verbatim<'buf>(src: &mut Cursor<&'buf [u8]>) -> Result<([u8; 3], &'buf [u8]), CursorError> {
+    match bulk(src)? {
+        [a, b, c, b':', text @ ..] => Ok(([*a, *b, *c], text)),
+        _ => Err(CursorError::Verbatim),
+    }
+}
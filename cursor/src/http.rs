@@ -0,0 +1,363 @@
+//! Parsing for the request line and headers of an
+//! [HTTP/1.1](https://www.rfc-editor.org/rfc/rfc9112) request.
+//!
+//! Only the framing is handled here: names and values are returned as the raw
+//! bytes of the buffer, without checking that the method or header names are
+//! valid tokens.
+use crate::{crlf, line, parse_radix, remaining, slice, with_rollback, Cursor, CursorError};
+use alloc::vec::Vec;
+
+/// The first line of an HTTP request, like `GET /index.html HTTP/1.1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RequestLine<'buf> {
+    /// The method, like `GET`.
+    pub method: &'buf [u8],
+    /// The request target, like `/index.html`.
+    pub target: &'buf [u8],
+    /// The protocol version, like `HTTP/1.1`.
+    pub version: &'buf [u8],
+}
+
+/// A header's name and value, like `(b"Host", b"example.com")`.
+pub type Header<'buf> = (&'buf [u8], &'buf [u8]);
+
+/// Read an HTTP request line from a cursor, advancing the position just past
+/// the `\n`.
+///
+/// # Errors
+///
+/// If there's no terminating `\r\n`, then `CursorError::Unterminated` is
+/// returned and the cursor is not advanced.
+///
+/// Otherwise, if the line isn't three parts separated by single spaces with a
+/// version starting with `HTTP/`, then `CursorError::Mismatch` is returned but
+/// the cursor is still advanced just past the `\n`.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::http::request_line;
+/// # use cursor::CursorError;
+/// let mut src: Cursor<&[u8]> = Cursor::new("GET /index.html HTTP/1.1\r\n".as_bytes());
+///
+/// let line = request_line(&mut src).unwrap();
+/// assert_eq!(line.method, b"GET");
+/// assert_eq!(line.target, b"/index.html");
+/// assert_eq!(line.version, b"HTTP/1.1");
+///
+/// let mut src: Cursor<&[u8]> = Cursor::new("GET /index.html\r\n".as_bytes());
+/// assert_eq!(request_line(&mut src), Err(CursorError::Mismatch));
+/// ```
+pub fn request_line<'buf>(
+    src: &mut Cursor<&'buf (impl AsRef<[u8]> + ?Sized)>,
+) -> Result<RequestLine<'buf>, CursorError> {
+    let mut parts = line(src)?.split(|&b| b == b' ');
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(method), Some(target), Some(version), None)
+            if !method.is_empty() && !target.is_empty() && version.starts_with(b"HTTP/") =>
+        {
+            Ok(RequestLine {
+                method,
+                target,
+                version,
+            })
+        }
+        _ => Err(CursorError::Mismatch),
+    }
+}
+
+/// Read a single header line from a cursor, advancing the position just past
+/// the `\n`, and return its name and value.
+///
+/// The value has leading and trailing whitespace removed.
+///
+/// # Errors
+///
+/// If there's no terminating `\r\n`, then `CursorError::Unterminated` is
+/// returned and the cursor is not advanced.
+///
+/// Otherwise, if the line doesn't start with a name followed directly by `:`,
+/// then `CursorError::Mismatch` is returned but the cursor is still advanced
+/// just past the `\n`.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::http::header;
+/// # use cursor::CursorError;
+/// let mut src: Cursor<&[u8]> =
+///     Cursor::new("Host:  example.com \r\nContent-Length:0\r\nbad line\r\n".as_bytes());
+///
+/// assert_eq!(header(&mut src), Ok((&b"Host"[..], &b"example.com"[..])));
+/// assert_eq!(header(&mut src), Ok((&b"Content-Length"[..], &b"0"[..])));
+/// assert_eq!(header(&mut src), Err(CursorError::Mismatch));
+/// ```
+pub fn header<'buf>(
+    src: &mut Cursor<&'buf (impl AsRef<[u8]> + ?Sized)>,
+) -> Result<Header<'buf>, CursorError> {
+    split_header(line(src)?)
+}
+
+/// Read a block of header lines from a cursor up to and including the empty
+/// line that ends it, advancing the position just past the empty line.
+///
+/// # Errors
+///
+/// If the empty line hasn't arrived yet, then `CursorError::Unterminated` is
+/// returned and the cursor is not advanced at all.
+///
+/// If a header is malformed, the same errors as [`header`] are returned, and
+/// the cursor is left just past the malformed header.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::http::{headers, request_line};
+/// let mut src: Cursor<&[u8]> =
+///     Cursor::new("GET / HTTP/1.1\r\nHost: example.com\r\nAccept: */*\r\n\r\nbody".as_bytes());
+///
+/// request_line(&mut src).unwrap();
+/// let headers = headers(&mut src).unwrap();
+/// assert_eq!(
+///     headers,
+///     [(&b"Host"[..], &b"example.com"[..]), (&b"Accept"[..], &b"*/*"[..])],
+/// );
+/// assert_eq!(&src.get_ref()[src.position() as usize..], b"body");
+/// ```
+/// Trying to read a header block that's incomplete:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::http::headers;
+/// let mut src: Cursor<&[u8]> = Cursor::new("Host: example.com\r\nAcc".as_bytes());
+///
+/// assert!(headers(&mut src).unwrap_err().not_enough_data());
+/// assert_eq!(src.position(), 0);
+/// ```
+pub fn headers<'buf>(
+    src: &mut Cursor<&'buf (impl AsRef<[u8]> + ?Sized)>,
+) -> Result<Vec<Header<'buf>>, CursorError> {
+    with_rollback(src, |src| {
+        let mut headers = Vec::new();
+        loop {
+            match line(src)? {
+                [] => return Ok(headers),
+                line => headers.push(split_header(line)?),
+            }
+        }
+    })
+}
+
+/// Splits a header line into its name and trimmed value.
+fn split_header(line: &[u8]) -> Result<Header<'_>, CursorError> {
+    let colon = line
+        .iter()
+        .position(|&b| b == b':')
+        .ok_or(CursorError::Mismatch)?;
+    let (name, value) = (&line[..colon], &line[colon + 1..]);
+    if name.is_empty() || name.iter().any(u8::is_ascii_whitespace) {
+        return Err(CursorError::Mismatch);
+    }
+    Ok((name, value.trim_ascii()))
+}
+
+/// A chunk of a body with `Transfer-Encoding: chunked`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Chunk<'buf> {
+    /// The payload of a chunk.
+    Data(&'buf [u8]),
+    /// The zero-size chunk that ends the body, with any trailer headers.
+    End(Vec<Header<'buf>>),
+}
+
+/// Read an entire chunk of a chunked body from a cursor: a hex size line, then
+/// that many bytes, then `\r\n`. For the zero-size chunk that ends the body,
+/// the trailers up to the empty line are read too. The position is advanced
+/// just past the chunk.
+///
+/// Chunk extensions after a `;` on the size line are ignored.
+///
+/// # Errors
+///
+/// If any part of the chunk hasn't arrived yet, then an error where
+/// [`CursorError::not_enough_data`] is `true` is returned and the cursor is
+/// not advanced at all.
+///
+/// If the size isn't hex, `CursorError::SizeInvalidDigit` is returned, or
+/// `CursorError::SizeOverflow` if it doesn't fit in a `u64` or is too large to
+/// ever fit in memory. If the payload isn't followed by
+/// `\r\n`, `CursorError::MissingCrlf` is returned. If a trailer is malformed,
+/// the same errors as [`header`] are returned. In all cases the cursor is left
+/// just past the bytes that were read.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::http::{chunk, Chunk};
+/// let mut src: Cursor<&[u8]> =
+///     Cursor::new("5\r\nHello\r\n7;ext=1\r\n, world\r\n0\r\nExpires: never\r\n\r\n".as_bytes());
+///
+/// assert_eq!(chunk(&mut src), Ok(Chunk::Data(b"Hello")));
+/// assert_eq!(chunk(&mut src), Ok(Chunk::Data(b", world")));
+/// assert_eq!(chunk(&mut src), Ok(Chunk::End(vec![(&b"Expires"[..], &b"never"[..])])));
+/// ```
+/// Trying to read a chunk whose size can't fit in memory:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::http::chunk;
+/// # use cursor::CursorError;
+/// let mut src: Cursor<&[u8]> = Cursor::new("ffffffffffffffff\r\nabc".as_bytes());
+/// assert_eq!(chunk(&mut src), Err(CursorError::SizeOverflow));
+///
+/// let mut src: Cursor<&[u8]> = Cursor::new("7fffffffffffffff\r\nabc".as_bytes());
+/// assert_eq!(chunk(&mut src), Err(CursorError::SizeOverflow));
+/// ```
+/// Trying to read a chunk whose size isn't just hex digits:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::http::chunk;
+/// # use cursor::CursorError;
+/// let mut src: Cursor<&[u8]> = Cursor::new("+5\r\nhello\r\n".as_bytes());
+///
+/// assert_eq!(chunk(&mut src), Err(CursorError::SizeInvalidDigit));
+/// ```
+pub fn chunk<'buf>(
+    src: &mut Cursor<&'buf (impl AsRef<[u8]> + ?Sized)>,
+) -> Result<Chunk<'buf>, CursorError> {
+    with_rollback(src, |src| match chunk_size(src)? {
+        0 => headers(src).map(Chunk::End),
+        len => {
+            let payload = slice(src, len)?;
+            crlf(src)?;
+            Ok(Chunk::Data(payload))
+        }
+    })
+}
+
+/// A decoder for a body with `Transfer-Encoding: chunked` that yields the
+/// payload as it arrives.
+///
+/// Unlike [`chunk`], which waits for an entire chunk, this returns whatever
+/// part of a chunk's payload is buffered, and remembers how much of the chunk
+/// is left. This way a chunk larger than the buffer can still be read, and
+/// the payload can be consumed from the buffer before the rest arrives. Trailer
+/// headers are skipped.
+///
+/// # Examples
+///
+/// Decoding a body that arrives in pieces, like it would into a `ReadBuf`
+/// from the `readbuf` crate:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::http::ChunkedDecoder;
+/// let mut decoder = ChunkedDecoder::new();
+/// let mut body = Vec::new();
+///
+/// let mut buf = b"c\r\nHello".to_vec();
+/// let mut src = Cursor::new(&buf[..]);
+/// while let Ok(Some(payload)) = decoder.decode(&mut src) {
+///     body.extend_from_slice(payload);
+/// }
+/// // Everything that was read can be consumed from the buffer
+/// buf.drain(..src.position() as usize);
+///
+/// buf.extend_from_slice(b", world\r\n0\r\n\r\n");
+/// let mut src = Cursor::new(&buf[..]);
+/// while let Some(payload) = decoder.decode(&mut src).unwrap() {
+///     body.extend_from_slice(payload);
+/// }
+///
+/// assert_eq!(body, b"Hello, world");
+/// assert!(decoder.is_done());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ChunkedDecoder {
+    state: DecoderState,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+enum DecoderState {
+    /// Waiting for a size line.
+    #[default]
+    Size,
+    /// In the payload of a chunk, with this many bytes left.
+    Data(u64),
+    /// Waiting for the `\r\n` after a payload.
+    Crlf,
+    /// Skipping trailers until the empty line.
+    Trailers,
+    /// The body has ended.
+    Done,
+}
+
+impl ChunkedDecoder {
+    /// Creates a new [`ChunkedDecoder`] at the start of a body.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` once the end of the body has been decoded.
+    pub fn is_done(&self) -> bool {
+        matches!(self.state, DecoderState::Done)
+    }
+
+    /// Decodes the next piece of payload from a cursor, returning `None` once
+    /// the body has ended.
+    ///
+    /// The cursor is advanced past everything that was decoded, even when an
+    /// error is returned, and the decoder remembers where it is in the body.
+    /// The next call must pass a cursor at the same point of the body, so
+    /// bytes before the cursor's position can be discarded.
+    ///
+    /// # Errors
+    ///
+    /// If more data is needed, an error where [`CursorError::not_enough_data`]
+    /// is `true` is returned. Otherwise the same errors as [`chunk`] are
+    /// returned.
+    pub fn decode<'buf>(
+        &mut self,
+        src: &mut Cursor<&'buf (impl AsRef<[u8]> + ?Sized)>,
+    ) -> Result<Option<&'buf [u8]>, CursorError> {
+        loop {
+            self.state = match self.state {
+                DecoderState::Size => match chunk_size(src)? {
+                    0 => DecoderState::Trailers,
+                    len => DecoderState::Data(len),
+                },
+                DecoderState::Data(0) => DecoderState::Crlf,
+                DecoderState::Data(left) => {
+                    let len = left.min(remaining(src) as u64);
+                    if len == 0 {
+                        return Err(CursorError::Incomplete);
+                    }
+                    let payload = slice(src, len)?;
+                    self.state = DecoderState::Data(left - len);
+                    return Ok(Some(payload));
+                }
+                DecoderState::Crlf => {
+                    crlf(src)?;
+                    DecoderState::Size
+                }
+                DecoderState::Trailers => match line(src)? {
+                    [] => DecoderState::Done,
+                    line => {
+                        split_header(line)?;
+                        DecoderState::Trailers
+                    }
+                },
+                DecoderState::Done => return Ok(None),
+            };
+        }
+    }
+}
+
+/// Reads a chunk size line, ignoring any chunk extensions.
+fn chunk_size(src: &mut Cursor<&(impl AsRef<[u8]> + ?Sized)>) -> Result<u64, CursorError> {
+    let line = line(src)?;
+    let digits = line.split(|&b| b == b';').next().unwrap_or(line);
+    let digits = digits.trim_ascii_end();
+    // Only bare hex digits, without the sign that `parse_radix` allows
+    if !digits.iter().all(u8::is_ascii_hexdigit) {
+        return Err(CursorError::SizeInvalidDigit);
+    }
+    parse_radix(digits, 16)
+}
@@ -0,0 +1,43 @@
+//! A stand-in for [`std::io::Cursor`] when the `std` feature is disabled.
+
+/// A minimal version of [`std::io::Cursor`] for `no_std` environments.
+///
+/// It has the same constructor and methods for getting and setting the
+/// position as the `std` type, which is all that the parsing functions use.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Cursor<T> {
+    inner: T,
+    pos: u64,
+}
+
+impl<T> Cursor<T> {
+    /// Creates a new cursor wrapping the provided buffer, with a position of 0.
+    pub const fn new(inner: T) -> Self {
+        Cursor { inner, pos: 0 }
+    }
+
+    /// Consumes this cursor, returning the underlying buffer.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Gets a reference to the underlying buffer.
+    pub const fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying buffer.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Returns the current position of this cursor.
+    pub const fn position(&self) -> u64 {
+        self.pos
+    }
+
+    /// Sets the position of this cursor.
+    pub fn set_position(&mut self, pos: u64) {
+        self.pos = pos;
+    }
+}
@@ -1,7 +1,59 @@
 //! Utilities for working with [`Cursor<&[u8]>`](Cursor)s.
-use std::fmt;
+//!
+//! Functions that return owned values, like [`byte`] and [`integer`], accept
+//! a cursor over any buffer implementing `AsRef<[u8]>`, such as a
+//! `Cursor<Vec<u8>>`. Functions that return slices of the buffer, like [`line`],
+//! accept a cursor over any borrowed buffer, such as a `Cursor<&Vec<u8>>`, so
+//! that the slices they return can outlive the borrow of the cursor.
+//!
+//! ```
+//! # use std::io::Cursor;
+//! # use cursor::{integer, line};
+//! let mut owned: Cursor<Vec<u8>> = Cursor::new(b"100\r\n".to_vec());
+//! assert_eq!(integer(&mut owned), Ok(100));
+//!
+//! let buf: Vec<u8> = b"first\r\nsecond\r\n".to_vec();
+//! let mut borrowed: Cursor<&Vec<u8>> = Cursor::new(&buf);
+//! let first = line(&mut borrowed).unwrap();
+//! let second = line(&mut borrowed).unwrap();
+//! assert_eq!((first, second), (&b"first"[..], &b"second"[..]));
+//! ```
+//!
+//! # `no_std`
+//!
+//! Disabling the default `std` feature makes this crate `#![no_std]`, only
+//! requiring `alloc`. In that case, [`std::io::Cursor`] is replaced by a
+//! minimal `Cursor` type exported from this crate with the same methods.
+#![cfg_attr(not(feature = "std"), no_std)]
+extern crate alloc;
+
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
+#[cfg(feature = "std")]
 use std::io::Cursor;
 
+#[cfg(not(feature = "std"))]
+pub use crate::io::Cursor;
+
+#[cfg(feature = "bytes")]
+pub mod buf;
+pub mod chain;
+#[cfg(feature = "serde")]
+pub mod de;
+#[cfg(feature = "std")]
+pub mod encode;
+pub mod fields;
+pub mod frame;
+pub mod http;
+#[cfg(not(feature = "std"))]
+mod io;
+
+#[cfg(feature = "serde")]
+pub use de::{from_cursor, from_slice};
+pub use fields::{fields, words, Fields};
+
 /// Error type for reading bytes.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum CursorError {
@@ -11,13 +63,69 @@ pub enum CursorError {
     /// A specific number of bytes were requested (like through [`byte`] or [`slice()`]),
     /// but there weren't that many bytes remaining.
     Incomplete,
-    /// `i64` not parsable from ASCII.
-    Integer,
-    /// `u64` not parsable from ASCII.
-    Size,
+    /// Integer not parsable from ASCII, because it's empty or starts with a
+    /// byte that isn't a digit.
+    InvalidDigit,
+    /// `u64` not parsable from ASCII, because it's empty or starts with a byte
+    /// that isn't a digit.
+    SizeInvalidDigit,
+    /// `f64` not parsable from ASCII.
+    Double,
+    /// `bool` not parsable from ASCII.
+    Boolean,
+    /// A line was longer than the allowed maximum.
+    LineTooLong,
+    /// A frame started with a byte that isn't a known frame type.
+    FrameType(u8),
+    /// Aggregate frames were nested deeper than the allowed maximum.
+    TooDeep,
+    /// A verbatim string didn't start with a 3-byte format and a `:`.
+    Verbatim,
+    /// A payload wasn't followed by the expected `\r\n`.
+    MissingCrlf,
+    /// Varint is longer than 10 bytes or overflows a `u64`.
+    Varint,
+    /// Line is not valid UTF-8, with the offset of the first invalid byte
+    /// from the start of the line.
+    Utf8(usize),
+    /// The bytes at the cursor didn't match what was expected.
+    Mismatch,
+    /// A line had fewer fields than expected.
+    MissingField,
+    /// Integer parsed from ASCII doesn't fit in the requested type.
+    Overflow,
+    /// `u64` parsed from ASCII doesn't fit in a `u64`.
+    SizeOverflow,
+    /// A length prefix was larger than the allowed maximum.
+    FrameTooLarge,
+    /// Line is not valid hex.
+    Hex,
+    /// Line is not valid base64.
+    Base64,
+    /// A quoted string had no closing `"` before the end of the line.
+    UnterminatedQuote,
+    /// A quoted string had a backslash escape that isn't recognized.
+    BadEscape,
 }
 
+#[allow(non_upper_case_globals)]
 impl CursorError {
+    /// Integer not parsable from ASCII.
+    ///
+    /// This is an alias for [`CursorError::InvalidDigit`], so it doesn't match
+    /// integers that overflow.
+    #[deprecated(note = "use `CursorError::InvalidDigit` or `CursorError::Overflow` instead")]
+    pub const Integer: CursorError = CursorError::InvalidDigit;
+
+    /// `u64` not parsable from ASCII.
+    ///
+    /// This is an alias for [`CursorError::SizeInvalidDigit`], so it doesn't
+    /// match sizes that overflow.
+    #[deprecated(
+        note = "use `CursorError::SizeInvalidDigit` or `CursorError::SizeOverflow` instead"
+    )]
+    pub const Size: CursorError = CursorError::SizeInvalidDigit;
+
     pub fn not_enough_data(&self) -> bool {
         matches!(self, Self::Unterminated(_) | Self::Incomplete)
     }
@@ -30,14 +138,137 @@ impl fmt::Display for CursorError {
                 write!(f, "unterminated line of {line_len} bytes so far")
             }
             Self::Incomplete => "incomplete".fmt(f),
-            Self::Integer => "could not parse integer".fmt(f),
-            Self::Size => "could not parse size".fmt(f),
+            Self::InvalidDigit => "invalid digit in integer".fmt(f),
+            Self::SizeInvalidDigit => "invalid digit in size".fmt(f),
+            Self::Double => "could not parse double".fmt(f),
+            Self::Boolean => "could not parse boolean".fmt(f),
+            Self::LineTooLong => "line too long".fmt(f),
+            Self::FrameType(b) => write!(f, "invalid frame type byte `{}`", b.escape_ascii()),
+            Self::TooDeep => "frames nested too deeply".fmt(f),
+            Self::Verbatim => "verbatim string missing format".fmt(f),
+            Self::MissingCrlf => "expected \\r\\n after payload".fmt(f),
+            Self::Varint => "varint overflows 64 bits".fmt(f),
+            Self::Utf8(offset) => write!(f, "invalid UTF-8 at byte {offset} of line"),
+            Self::Mismatch => "unexpected bytes".fmt(f),
+            Self::MissingField => "missing field".fmt(f),
+            Self::Overflow => "integer out of range".fmt(f),
+            Self::SizeOverflow => "size out of range".fmt(f),
+            Self::FrameTooLarge => "frame too large".fmt(f),
+            Self::Hex => "invalid hex".fmt(f),
+            Self::Base64 => "invalid base64".fmt(f),
+            Self::UnterminatedQuote => "unterminated quoted string".fmt(f),
+            Self::BadEscape => "invalid escape in quoted string".fmt(f),
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for CursorError {}
 
+/// A [`CursorError`] along with where in the buffer it happened.
+///
+/// Along with the byte offset, the position is given as a line and column,
+/// where lines are separated by `\r\n`, for when the buffer holds something
+/// like a config file.
+///
+/// This is returned by [`with_context`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CursorErrorAt {
+    kind: CursorError,
+    offset: u64,
+    line: u64,
+    column: u64,
+    snippet: Vec<u8>,
+}
+
+impl CursorErrorAt {
+    /// How many bytes of the buffer are captured in [`CursorErrorAt::snippet`].
+    pub const SNIPPET_LEN: usize = 16;
+
+    /// Creates a new [`CursorErrorAt`] for an error that happened while parsing
+    /// from `offset` in `buf`.
+    pub fn new(kind: CursorError, buf: &[u8], offset: u64) -> Self {
+        let start = (offset as usize).min(buf.len());
+        let end = start.saturating_add(Self::SNIPPET_LEN).min(buf.len());
+
+        let before = &buf[..start];
+        let mut line_start = 0;
+        let mut lines = 0;
+        while let Some(i) = find(&before[line_start..], b"\r\n") {
+            line_start += i + 2;
+            lines += 1;
+        }
+
+        CursorErrorAt {
+            kind,
+            offset,
+            line: lines + 1,
+            column: (start - line_start) as u64 + 1,
+            snippet: buf[start..end].to_vec(),
+        }
+    }
+
+    /// Returns the underlying error.
+    pub fn kind(&self) -> CursorError {
+        self.kind
+    }
+
+    /// Returns the position of the cursor when the failed parse started.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the 1-based line of [`CursorErrorAt::offset`], counting the
+    /// `\r\n`s before it.
+    pub fn line(&self) -> u64 {
+        self.line
+    }
+
+    /// Returns the 1-based column of [`CursorErrorAt::offset`] in bytes,
+    /// counting from the start of its line.
+    pub fn column(&self) -> u64 {
+        self.column
+    }
+
+    /// Returns up to [`CursorErrorAt::SNIPPET_LEN`] bytes of the buffer
+    /// starting at [`CursorErrorAt::offset`].
+    pub fn snippet(&self) -> &[u8] {
+        &self.snippet
+    }
+
+    /// See [`CursorError::not_enough_data`].
+    pub fn not_enough_data(&self) -> bool {
+        self.kind.not_enough_data()
+    }
+}
+
+impl From<CursorErrorAt> for CursorError {
+    fn from(err: CursorErrorAt) -> Self {
+        err.kind
+    }
+}
+
+impl fmt::Display for CursorErrorAt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {} (byte {}, near \"{}\")",
+            self.kind,
+            self.line,
+            self.column,
+            self.offset,
+            self.snippet.escape_ascii()
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CursorErrorAt {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.kind)
+    }
+}
+
 /// Read a byte from a cursor, moving the position forward by 1.
 ///
 /// # Errors
@@ -64,10 +295,11 @@ impl std::error::Error for CursorError {}
 /// let third: Result<u8, CursorError> = byte(&mut src);
 /// assert_eq!(third, Err(CursorError::Incomplete));
 /// ```
-pub fn byte(src: &mut Cursor<&[u8]>) -> Result<u8, CursorError> {
+pub fn byte(src: &mut Cursor<impl AsRef<[u8]>>) -> Result<u8, CursorError> {
     let pos = src.position();
     let byte = *src
         .get_ref()
+        .as_ref()
         .get(pos as usize)
         .ok_or(CursorError::Incomplete)?;
 
@@ -84,8 +316,10 @@ pub fn byte(src: &mut Cursor<&[u8]>) -> Result<u8, CursorError> {
 /// and the cursor is not advanced.
 ///
 /// Otherwise, if the bytes between the start of the cursor and the `\r\n`
-/// do not represent the ASCII digit encoding of a `u64`, then `CursorError::Size`
-/// is returned but the cursor is still advanced just past the `\n`.
+/// do not represent the ASCII digit encoding of a `u64`, then the cursor is still
+/// advanced just past the `\n`, and `CursorError::SizeOverflow` is returned if
+/// they're digits that don't fit in a `u64`, or `CursorError::SizeInvalidDigit`
+/// otherwise.
 ///
 /// # Examples
 ///
@@ -98,6 +332,15 @@ pub fn byte(src: &mut Cursor<&[u8]>) -> Result<u8, CursorError> {
 /// let result: Result<u64, CursorError> = size(&mut src);
 /// assert_eq!(result, Ok(100));
 /// ```
+/// Telling apart a size that isn't a number from one that's too large:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{size, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("abc\r\n99999999999999999999\r\n".as_bytes());
+///
+/// assert_eq!(size(&mut src), Err(CursorError::SizeInvalidDigit));
+/// assert_eq!(size(&mut src), Err(CursorError::SizeOverflow));
+/// ```
 /// Trying to read from a slice that's incomplete:
 /// ```
 /// # use std::io::Cursor;
@@ -107,9 +350,121 @@ pub fn byte(src: &mut Cursor<&[u8]>) -> Result<u8, CursorError> {
 /// let result: Result<u64, CursorError> = size(&mut src);
 /// assert_eq!(result, Err(CursorError::Unterminated(3)));
 /// ```
-pub fn size(src: &mut Cursor<&[u8]>) -> Result<u64, CursorError> {
-    let line = line(src)?;
-    atoi::atoi(line).ok_or(CursorError::Size)
+pub fn size(src: &mut Cursor<impl AsRef<[u8]>>) -> Result<u64, CursorError> {
+    let line = line_in(src)?;
+    parse_size(line)
+}
+
+/// Read an ASCII-encoded, `\r\n`-terminated hexadecimal size from a cursor,
+/// advancing the position just past the `\n`.
+///
+/// Both uppercase and lowercase digits are accepted, without any `0x` prefix.
+///
+/// # Errors
+///
+/// If there's no terminating `\r\n`, then `CursorError::Unterminated` is returned
+/// and the cursor is not advanced.
+///
+/// Otherwise, if the bytes between the start of the cursor and the `\r\n`
+/// do not represent the ASCII hex digit encoding of a `u64`, then the same errors
+/// as [`number_radix`] are returned but the cursor is still advanced just past the `\n`.
+///
+/// # Examples
+///
+/// Reading from a slice successfully:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{size_hex, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("1A\r\nff\r\n".as_bytes());
+///
+/// assert_eq!(size_hex(&mut src), Ok(26));
+/// assert_eq!(size_hex(&mut src), Ok(255));
+/// ```
+/// Trying to read a line that isn't hex:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{size_hex, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("xyz\r\n".as_bytes());
+///
+/// assert_eq!(size_hex(&mut src), Err(CursorError::SizeInvalidDigit));
+/// assert_eq!(src.position(), 5);
+///
+/// // Like `size`, a sign is an invalid digit
+/// let mut src: Cursor<&[u8]> = Cursor::new("-5\r\n".as_bytes());
+/// assert_eq!(size_hex(&mut src), Err(CursorError::SizeInvalidDigit));
+/// ```
+pub fn size_hex(src: &mut Cursor<impl AsRef<[u8]>>) -> Result<u64, CursorError> {
+    number_radix(src, 16)
+}
+
+/// Read an ASCII-encoded, `\r\n`-terminated number in the given radix from
+/// a cursor, advancing the position just past the `\n`.
+///
+/// # Errors
+///
+/// If there's no terminating `\r\n`, then `CursorError::Unterminated` is returned
+/// and the cursor is not advanced.
+///
+/// Otherwise, if the bytes between the start of the cursor and the `\r\n`
+/// are digits in base `radix` that don't fit in a `T`, then `CursorError::SizeOverflow`
+/// is returned, or if they aren't digits at all, `CursorError::SizeInvalidDigit` is
+/// returned. Either way the cursor is still advanced just past the `\n`.
+///
+/// # Panics
+///
+/// This function panics if `radix` is not in the range from 2 to 36.
+///
+/// # Examples
+///
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{number_radix, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("777\r\n1010\r\n2\r\n".as_bytes());
+///
+/// assert_eq!(number_radix::<u16>(&mut src, 8), Ok(0o777));
+/// assert_eq!(number_radix::<u8>(&mut src, 2), Ok(0b1010));
+/// assert_eq!(number_radix::<u8>(&mut src, 2), Err(CursorError::SizeInvalidDigit));
+/// ```
+/// A leading `-` is only accepted when `T` is signed:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{number_radix, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("-ff\r\n-ff\r\n-ff\r\n".as_bytes());
+///
+/// assert_eq!(number_radix::<i16>(&mut src, 16), Ok(-0xff));
+/// assert_eq!(number_radix::<i8>(&mut src, 16), Err(CursorError::SizeOverflow));
+/// assert_eq!(number_radix::<u8>(&mut src, 16), Err(CursorError::SizeInvalidDigit));
+/// ```
+pub fn number_radix<T>(src: &mut Cursor<impl AsRef<[u8]>>, radix: u32) -> Result<T, CursorError>
+where
+    T: num_traits::Num,
+{
+    assert!((2..=36).contains(&radix), "radix must be in 2..=36");
+    let line = line_in(src)?;
+    parse_radix(line, radix)
+}
+
+/// Parses all of `text` as digits in base `radix`, with the errors for sizes.
+pub(crate) fn parse_radix<T>(text: &[u8], radix: u32) -> Result<T, CursorError>
+where
+    T: num_traits::Num,
+{
+    let s = core::str::from_utf8(text).map_err(|_| CursorError::SizeInvalidDigit)?;
+    T::from_str_radix(s, radix).map_err(|_| {
+        // The error type is opaque, so check the digits to tell why it failed.
+        // A `-` is only a sign if `T` is signed, otherwise it's an invalid digit.
+        let signed = T::from_str_radix("-1", radix).is_ok();
+        let digits = match s.strip_prefix('-') {
+            Some(digits) if signed => digits,
+            Some(_) => s,
+            None => s.strip_prefix('+').unwrap_or(s),
+        };
+        if !digits.is_empty() && digits.chars().all(|c| c.is_digit(radix)) {
+            CursorError::SizeOverflow
+        } else {
+            CursorError::SizeInvalidDigit
+        }
+    })
 }
 
 /// Read an ASCII-encoded, `\r\n`-terminated 64-bit signed integer from a cursor,
@@ -121,8 +476,8 @@ pub fn size(src: &mut Cursor<&[u8]>) -> Result<u64, CursorError> {
 /// and the cursor is not advanced.
 ///
 /// Otherwise, if the bytes between the start of the cursor and the `\r\n`
-/// do not represent the ASCII digit encoding of a `i64`, then `CursorError::Integer`
-/// is returned but the cursor is still advanced just past the `\n`.
+/// do not represent the ASCII digit encoding of a `i64`, then the same errors as
+/// [`number`] are returned but the cursor is still advanced just past the `\n`.
 ///
 /// # Examples
 ///
@@ -144,9 +499,216 @@ pub fn size(src: &mut Cursor<&[u8]>) -> Result<u64, CursorError> {
 /// let result: Result<i64, CursorError> = integer(&mut src);
 /// assert_eq!(result, Err(CursorError::Unterminated(3)));
 /// ```
-pub fn integer(src: &mut Cursor<&[u8]>) -> Result<i64, CursorError> {
-    let line = line(src)?;
-    atoi::atoi(line).ok_or(CursorError::Integer)
+pub fn integer(src: &mut Cursor<impl AsRef<[u8]>>) -> Result<i64, CursorError> {
+    number(src)
+}
+
+/// Read an ASCII-encoded, `\r\n`-terminated 128-bit signed integer from a
+/// cursor, advancing the position just past the `\n`.
+///
+/// This is for values that may not fit in an `i64`, like the digits of a
+/// RESP3 big number.
+///
+/// # Errors
+///
+/// The same errors as [`number`] are returned.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{integer_i128, CursorError};
+/// let mut src: Cursor<&[u8]> =
+///     Cursor::new("-170141183460469231731687303715884105728\r\n".as_bytes());
+///
+/// assert_eq!(integer_i128(&mut src), Ok(i128::MIN));
+/// ```
+pub fn integer_i128(src: &mut Cursor<impl AsRef<[u8]>>) -> Result<i128, CursorError> {
+    number(src)
+}
+
+/// Read an ASCII-encoded, `\r\n`-terminated 128-bit size from a cursor,
+/// advancing the position just past the `\n`.
+///
+/// # Errors
+///
+/// The same errors as [`size`] are returned, except that
+/// `CursorError::SizeOverflow` is only returned for sizes that don't fit in
+/// a `u128`.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{size_u128, CursorError};
+/// let mut src: Cursor<&[u8]> =
+///     Cursor::new("18446744073709551616\r\n999999999999999999999999999999999999999\r\n".as_bytes());
+///
+/// assert_eq!(size_u128(&mut src), Ok(u64::MAX as u128 + 1));
+/// assert_eq!(size_u128(&mut src), Err(CursorError::SizeOverflow));
+/// ```
+pub fn size_u128(src: &mut Cursor<impl AsRef<[u8]>>) -> Result<u128, CursorError> {
+    let line = line_in(src)?;
+    parse_size(line)
+}
+
+/// Read an ASCII-encoded, `\r\n`-terminated integer of any primitive type
+/// from a cursor, advancing the position just past the `\n`.
+///
+/// # Errors
+///
+/// If there's no terminating `\r\n`, then `CursorError::Unterminated` is returned
+/// and the cursor is not advanced.
+///
+/// Otherwise, if the bytes between the start of the cursor and the `\r\n`
+/// do not represent the ASCII digit encoding of a `T`, then the cursor is still
+/// advanced just past the `\n`, and `CursorError::Overflow` is returned if
+/// they're digits that don't fit in a `T`, or `CursorError::InvalidDigit`
+/// otherwise.
+///
+/// # Examples
+///
+/// Reading from a slice successfully:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{number, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("200\r\n-5\r\n".as_bytes());
+///
+/// assert_eq!(number::<u8>(&mut src), Ok(200));
+/// assert_eq!(number::<i32>(&mut src), Ok(-5));
+/// ```
+/// Trying to read a value that doesn't fit:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{number, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("256\r\n".as_bytes());
+///
+/// assert_eq!(number::<u8>(&mut src), Err(CursorError::Overflow));
+/// ```
+/// Trying to read something that isn't a number:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{number, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("abc\r\n".as_bytes());
+///
+/// assert_eq!(number::<u8>(&mut src), Err(CursorError::InvalidDigit));
+/// ```
+pub fn number<T>(src: &mut Cursor<impl AsRef<[u8]>>) -> Result<T, CursorError>
+where
+    T: atoi::FromRadix10SignedChecked,
+{
+    let line = line_in(src)?;
+    parse_number(line)
+}
+
+/// Parses the ASCII digits at the start of `text` like [`atoi::atoi`], but
+/// tells apart a value that doesn't fit from bytes that aren't digits.
+pub(crate) fn parse_number<T>(text: &[u8]) -> Result<T, CursorError>
+where
+    T: atoi::FromRadix10SignedChecked,
+{
+    match T::from_radix_10_signed_checked(text) {
+        (_, 0) => Err(CursorError::InvalidDigit),
+        (None, _) => Err(CursorError::Overflow),
+        (Some(n), _) => Ok(n),
+    }
+}
+
+/// Like [`parse_number`], but with the errors for sizes.
+pub(crate) fn parse_size<T>(text: &[u8]) -> Result<T, CursorError>
+where
+    T: atoi::FromRadix10SignedChecked,
+{
+    parse_number(text).map_err(|err| match err {
+        CursorError::Overflow => CursorError::SizeOverflow,
+        _ => CursorError::SizeInvalidDigit,
+    })
+}
+
+/// Read an ASCII-encoded, `\r\n`-terminated 64-bit float from a cursor,
+/// advancing the position just past the `\n`.
+///
+/// Along with the usual decimal and exponent forms, `inf`, `-inf`, and `nan`
+/// are accepted.
+///
+/// # Errors
+///
+/// If there's no terminating `\r\n`, then `CursorError::Unterminated` is returned
+/// and the cursor is not advanced.
+///
+/// Otherwise, if the bytes between the start of the cursor and the `\r\n`
+/// do not represent the ASCII encoding of a `f64`, then `CursorError::Double`
+/// is returned but the cursor is still advanced just past the `\n`.
+///
+/// # Examples
+///
+/// Reading from a slice successfully:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{double, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("1.5\r\n-inf\r\nnan\r\n".as_bytes());
+///
+/// assert_eq!(double(&mut src), Ok(1.5));
+/// assert_eq!(double(&mut src), Ok(f64::NEG_INFINITY));
+/// assert!(double(&mut src).unwrap().is_nan());
+/// ```
+/// Trying to read a line that isn't a float:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{double, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("1.5.2\r\n".as_bytes());
+///
+/// assert_eq!(double(&mut src), Err(CursorError::Double));
+/// assert_eq!(src.position(), 7);
+/// ```
+pub fn double(src: &mut Cursor<impl AsRef<[u8]>>) -> Result<f64, CursorError> {
+    let line = line_in(src)?;
+    core::str::from_utf8(line)
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .ok_or(CursorError::Double)
+}
+
+/// Read an ASCII-encoded, `\r\n`-terminated boolean from a cursor, advancing
+/// the position just past the `\n`.
+///
+/// The accepted spellings are `t`, `true`, and `1` for `true`, and `f`, `false`,
+/// and `0` for `false`. Letters are case-sensitive.
+///
+/// # Errors
+///
+/// If there's no terminating `\r\n`, then `CursorError::Unterminated` is returned
+/// and the cursor is not advanced.
+///
+/// Otherwise, if the line isn't one of the accepted spellings, then
+/// `CursorError::Boolean` is returned but the cursor is still advanced just
+/// past the `\n`.
+///
+/// # Examples
+///
+/// Reading from a slice successfully:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{boolean, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("t\r\nfalse\r\n1\r\n".as_bytes());
+///
+/// assert_eq!(boolean(&mut src), Ok(true));
+/// assert_eq!(boolean(&mut src), Ok(false));
+/// assert_eq!(boolean(&mut src), Ok(true));
+/// ```
+/// Trying to read a line that isn't a boolean:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{boolean, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("yes\r\n".as_bytes());
+///
+/// assert_eq!(boolean(&mut src), Err(CursorError::Boolean));
+/// assert_eq!(src.position(), 5);
+/// ```
+pub fn boolean(src: &mut Cursor<impl AsRef<[u8]>>) -> Result<bool, CursorError> {
+    match line_in(src)? {
+        b"t" | b"true" | b"1" => Ok(true),
+        b"f" | b"false" | b"0" => Ok(false),
+        _ => Err(CursorError::Boolean),
+    }
 }
 
 /// Read a `\r\n`-terminated line from a cursor, advancing the position
@@ -176,63 +738,1703 @@ pub fn integer(src: &mut Cursor<&[u8]>) -> Result<i64, CursorError> {
 /// let result: Result<&[u8], CursorError> = line(&mut src);
 /// assert_eq!(result, Err(CursorError::Unterminated(13)));
 /// ```
-pub fn line<'buf>(src: &mut Cursor<&'buf [u8]>) -> Result<&'buf [u8], CursorError> {
-    let rem = src
-        .get_ref()
-        .get(src.position() as usize..)
-        .expect("position in bounds");
-
-    let index = rem
-        .len()
-        .checked_sub(1)
-        .and_then(|end| (0..end).find(|&i| [rem[i], rem[i + 1]] == *b"\r\n"))
-        .ok_or(CursorError::Unterminated(rem.len()))?;
-
-    src.set_position(src.position() + index as u64 + 2);
-    Ok(&rem[..index])
+pub fn line<'buf>(
+    src: &mut Cursor<&'buf (impl AsRef<[u8]> + ?Sized)>,
+) -> Result<&'buf [u8], CursorError> {
+    line_with(src, b"\r\n")
 }
 
-/// Read `len` bytes from a cursor, advancing the position to the next unread byte.
+/// Read a `\r\n`-terminated UTF-8 line from a cursor, advancing the position
+/// just past the `\n`.
 ///
 /// # Errors
 ///
-/// If there aren't `len` bytes remaining, `CursorError::Incomplete` is returned
+/// If there's no terminating `\r\n`, then `CursorError::Unterminated` is returned
 /// and the cursor is not advanced.
 ///
+/// Otherwise, if the line is not valid UTF-8, then `CursorError::Utf8` is
+/// returned but the cursor is still advanced just past the `\n`.
+///
 /// # Examples
 ///
 /// Reading from a slice successfully:
 /// ```
 /// # use std::io::Cursor;
-/// # use cursor::{slice, CursorError};
-/// let mut src: Cursor<&[u8]> = Cursor::new("Hello, world!".as_bytes());
+/// # use cursor::{str_line, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("Hello, world!\r\n".as_bytes());
 ///
-/// let result: Result<&[u8], CursorError> = slice(&mut src, 5);
-/// assert_eq!(result, Ok("Hello".as_bytes()));
+/// let result: Result<&str, CursorError> = str_line(&mut src);
+/// assert_eq!(result, Ok("Hello, world!"));
+/// ```
+/// Trying to read a line that isn't UTF-8:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{str_line, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new(b"ab\xFF\r\n");
 ///
-/// // Position is advanced past "Hello" now
-/// let result: Result<&[u8], CursorError> = slice(&mut src, 5);
-/// assert_eq!(result, Ok(", wor".as_bytes()));
+/// let result: Result<&str, CursorError> = str_line(&mut src);
+/// assert_eq!(result, Err(CursorError::Utf8(2)));
 /// ```
-/// Trying to read from a slice that's incomplete:
+pub fn str_line<'buf>(
+    src: &mut Cursor<&'buf (impl AsRef<[u8]> + ?Sized)>,
+) -> Result<&'buf str, CursorError> {
+    let line = line(src)?;
+    core::str::from_utf8(line).map_err(|err| CursorError::Utf8(err.valid_up_to()))
+}
+
+/// Read a `\r\n`-terminated line of hex digits from a cursor and decode it,
+/// advancing the position just past the `\n`.
+///
+/// Both uppercase and lowercase digits are accepted.
+///
+/// # Errors
+///
+/// If there's no terminating `\r\n`, then `CursorError::Unterminated` is returned
+/// and the cursor is not advanced.
+///
+/// Otherwise, if the line has an odd length or a byte that isn't a hex digit,
+/// then `CursorError::Hex` is returned but the cursor is still advanced just past
+/// the `\n`.
+///
+/// # Examples
 /// ```
 /// # use std::io::Cursor;
-/// # use cursor::{slice, CursorError};
-/// let mut src: Cursor<&[u8]> = Cursor::new("Hello, world!".as_bytes());
+/// # use cursor::{line_hex, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("DEADbeef\r\nabc\r\n".as_bytes());
 ///
-/// // Try to read too many bytes
-/// let result: Result<&[u8], CursorError> = slice(&mut src, 20);
-/// assert_eq!(result, Err(CursorError::Incomplete));
+/// assert_eq!(line_hex(&mut src), Ok(vec![0xDE, 0xAD, 0xBE, 0xEF]));
+/// assert_eq!(line_hex(&mut src), Err(CursorError::Hex));
 /// ```
-pub fn slice<'buf>(src: &mut Cursor<&'buf [u8]>, len: u64) -> Result<&'buf [u8], CursorError> {
-    let start = src.position();
-    let end = start.checked_add(len).expect("overflow");
+pub fn line_hex(src: &mut Cursor<impl AsRef<[u8]>>) -> Result<Vec<u8>, CursorError> {
+    let line = line_in(src)?;
+    if line.len() % 2 != 0 {
+        return Err(CursorError::Hex);
+    }
 
-    let slice = src
-        .get_ref()
-        .get(start as usize..end as usize)
-        .ok_or(CursorError::Incomplete)?;
+    let digit = |b: u8| (b as char).to_digit(16).ok_or(CursorError::Hex);
+    line.chunks_exact(2)
+        .map(|pair| Ok((digit(pair[0])? << 4 | digit(pair[1])?) as u8))
+        .collect()
+}
 
-    src.set_position(end);
-    Ok(slice)
+/// Read a `\r\n`-terminated line of standard, padded base64 from a cursor and
+/// decode it, advancing the position just past the `\n`.
+///
+/// # Errors
+///
+/// If there's no terminating `\r\n`, then `CursorError::Unterminated` is returned
+/// and the cursor is not advanced.
+///
+/// Otherwise, if the line isn't valid base64, then `CursorError::Base64` is
+/// returned but the cursor is still advanced just past the `\n`.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{line_base64, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("SGVsbG8=\r\nSGVsbG8\r\n".as_bytes());
+///
+/// assert_eq!(line_base64(&mut src), Ok(b"Hello".to_vec()));
+/// assert_eq!(line_base64(&mut src), Err(CursorError::Base64));
+/// ```
+#[cfg(feature = "base64")]
+pub fn line_base64(src: &mut Cursor<impl AsRef<[u8]>>) -> Result<Vec<u8>, CursorError> {
+    use base64::Engine;
+
+    let line = line_in(src)?;
+    base64::engine::general_purpose::STANDARD
+        .decode(line)
+        .map_err(|_| CursorError::Base64)
+}
+
+/// Returns an iterator over the `\r\n`-terminated lines of a cursor, advancing
+/// the position past each line as it's yielded.
+///
+/// Iteration stops cleanly at the first unterminated line, leaving the cursor
+/// at its start so that the caller can refill the buffer and continue.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{lines, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("one\r\ntwo\r\nthr".as_bytes());
+///
+/// let mut lines = lines(&mut src);
+/// assert_eq!(lines.next(), Some(Ok("one".as_bytes())));
+/// assert_eq!(lines.next(), Some(Ok("two".as_bytes())));
+/// assert_eq!(lines.next(), None);
+///
+/// // "thr" hasn't been consumed yet
+/// assert_eq!(lines.remaining(), 3);
+/// ```
+pub fn lines<'a, 'buf, B: AsRef<[u8]> + ?Sized>(
+    src: &'a mut Cursor<&'buf B>,
+) -> Lines<'a, 'buf, B> {
+    Lines {
+        src,
+        max_len: usize::MAX,
+        done: false,
+    }
+}
+
+/// An iterator over the `\r\n`-terminated lines of a cursor.
+///
+/// This is returned by [`lines`].
+#[derive(Debug)]
+pub struct Lines<'a, 'buf, B: ?Sized> {
+    src: &'a mut Cursor<&'buf B>,
+    max_len: usize,
+    done: bool,
+}
+
+impl<'a, 'buf, B: AsRef<[u8]> + ?Sized> Lines<'a, 'buf, B> {
+    /// Limits lines to `max_len` bytes, like [`line_bounded`].
+    ///
+    /// A longer line is yielded as `CursorError::LineTooLong`, after which
+    /// iteration stops.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::io::Cursor;
+    /// # use cursor::{lines, CursorError};
+    /// let mut src: Cursor<&[u8]> = Cursor::new("one\r\nthree\r\n".as_bytes());
+    ///
+    /// let mut lines = lines(&mut src).max_len(3);
+    /// assert_eq!(lines.next(), Some(Ok("one".as_bytes())));
+    /// assert_eq!(lines.next(), Some(Err(CursorError::LineTooLong)));
+    /// assert_eq!(lines.next(), None);
+    /// ```
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Returns the number of bytes that haven't been yielded as lines yet.
+    pub fn remaining(&self) -> usize {
+        remaining(self.src)
+    }
+}
+
+impl<'a, 'buf, B: AsRef<[u8]> + ?Sized> Iterator for Lines<'a, 'buf, B> {
+    type Item = Result<&'buf [u8], CursorError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match line_bounded(self.src, self.max_len) {
+            Ok(line) => Some(Ok(line)),
+            Err(err) => {
+                self.done = true;
+                if err.not_enough_data() {
+                    None
+                } else {
+                    Some(Err(err))
+                }
+            }
+        }
+    }
+}
+
+impl<'a, 'buf, B: AsRef<[u8]> + ?Sized> core::iter::FusedIterator for Lines<'a, 'buf, B> {}
+
+/// Read a line ending with `terminator` from a cursor, advancing the position
+/// just past the terminator.
+///
+/// This is useful for protocols that use something other than `\r\n`, such
+/// as a bare `\n`.
+///
+/// # Errors
+///
+/// If there's no `terminator`, then `CursorError::Unterminated` is returned.
+///
+/// # Panics
+///
+/// This function panics if `terminator` is empty.
+///
+/// # Examples
+///
+/// Reading from a slice successfully:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{line_with, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("first\nsecond;;".as_bytes());
+///
+/// assert_eq!(line_with(&mut src, b"\n"), Ok("first".as_bytes()));
+/// assert_eq!(line_with(&mut src, b";;"), Ok("second".as_bytes()));
+/// ```
+/// Trying to read from a slice that's incomplete:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{line_with, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("Hello, world!".as_bytes());
+///
+/// let result: Result<&[u8], CursorError> = line_with(&mut src, b"\n");
+/// assert_eq!(result, Err(CursorError::Unterminated(13)));
+/// ```
+pub fn line_with<'buf>(
+    src: &mut Cursor<&'buf (impl AsRef<[u8]> + ?Sized)>,
+    terminator: &[u8],
+) -> Result<&'buf [u8], CursorError> {
+    let range = line_range(src, terminator)?;
+    Ok(&buf(src)[range])
+}
+
+/// Read a `\r\n`-terminated line from a cursor over any buffer, returning the
+/// line borrowed from the cursor.
+fn line_in<T: AsRef<[u8]>>(src: &mut Cursor<T>) -> Result<&[u8], CursorError> {
+    let range = line_range(src, b"\r\n")?;
+    Ok(&src.get_ref().as_ref()[range])
+}
+
+/// Read a `\r\n`-terminated line of at most `max_len` bytes (not counting the
+/// `\r\n`) from a cursor, advancing the position just past the `\n`.
+///
+/// Unlike [`line`], at most `max_len + 2` bytes are scanned, so a peer that
+/// never sends `\r\n` can be detected without scanning everything it sent.
+///
+/// # Errors
+///
+/// If there's no terminating `\r\n` within the first `max_len + 2` bytes,
+/// then `CursorError::LineTooLong` is returned. Otherwise if there's no
+/// terminating `\r\n` yet, `CursorError::Unterminated` is returned. In both
+/// cases the cursor is not advanced.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{line_bounded, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("Hello\r\nHello, world!".as_bytes());
+///
+/// assert_eq!(line_bounded(&mut src, 5), Ok("Hello".as_bytes()));
+/// assert_eq!(line_bounded(&mut src, 20), Err(CursorError::Unterminated(13)));
+/// assert_eq!(line_bounded(&mut src, 5), Err(CursorError::LineTooLong));
+/// ```
+pub fn line_bounded<'buf>(
+    src: &mut Cursor<&'buf (impl AsRef<[u8]> + ?Sized)>,
+    max_len: usize,
+) -> Result<&'buf [u8], CursorError> {
+    let range = line_range_bounded(src, b"\r\n", max_len)?;
+    Ok(&buf(src)[range])
+}
+
+/// Finds the line ending with `terminator` at the front of a cursor, advancing
+/// the position just past the terminator and returning the range of the line
+/// in the buffer.
+fn line_range(
+    src: &mut Cursor<impl AsRef<[u8]>>,
+    terminator: &[u8],
+) -> Result<Range<usize>, CursorError> {
+    line_range_bounded(src, terminator, usize::MAX)
+}
+
+/// Like [`line_range`], but only scanning far enough for a line of `max_len` bytes.
+fn line_range_bounded(
+    src: &mut Cursor<impl AsRef<[u8]>>,
+    terminator: &[u8],
+    max_len: usize,
+) -> Result<Range<usize>, CursorError> {
+    assert!(!terminator.is_empty(), "terminator must not be empty");
+    let rem = rest(src);
+    let scan_len = max_len.saturating_add(terminator.len());
+
+    let index =
+        find(&rem[..rem.len().min(scan_len)], terminator).ok_or(if rem.len() >= scan_len {
+            CursorError::LineTooLong
+        } else {
+            CursorError::Unterminated(rem.len())
+        })?;
+
+    let start = src.position() as usize;
+    src.set_position((start + index + terminator.len()) as u64);
+    Ok(start..start + index)
+}
+
+/// Read `len` bytes from a cursor, advancing the position to the next unread byte.
+///
+/// # Errors
+///
+/// If there aren't `len` bytes remaining, `CursorError::Incomplete` is returned
+/// and the cursor is not advanced. If `len` is so large that the bytes could
+/// never fit in memory, `CursorError::SizeOverflow` is returned instead.
+///
+/// # Examples
+///
+/// Reading from a slice successfully:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{slice, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("Hello, world!".as_bytes());
+///
+/// let result: Result<&[u8], CursorError> = slice(&mut src, 5);
+/// assert_eq!(result, Ok("Hello".as_bytes()));
+///
+/// // Position is advanced past "Hello" now
+/// let result: Result<&[u8], CursorError> = slice(&mut src, 5);
+/// assert_eq!(result, Ok(", wor".as_bytes()));
+/// ```
+/// Trying to read from a slice that's incomplete:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{slice, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("Hello, world!".as_bytes());
+///
+/// // Try to read too many bytes
+/// let result: Result<&[u8], CursorError> = slice(&mut src, 20);
+/// assert_eq!(result, Err(CursorError::Incomplete));
+/// ```
+pub fn slice<'buf>(
+    src: &mut Cursor<&'buf (impl AsRef<[u8]> + ?Sized)>,
+    len: u64,
+) -> Result<&'buf [u8], CursorError> {
+    let range = slice_range(src, len)?;
+    Ok(&buf(src)[range])
+}
+
+/// Finds `len` bytes at the front of a cursor, advancing the position past them
+/// and returning their range in the buffer.
+fn slice_range(src: &mut Cursor<impl AsRef<[u8]>>, len: u64) -> Result<Range<usize>, CursorError> {
+    let start = src.position();
+    // No buffer can be longer than `isize::MAX` bytes
+    let end = usize::try_from(len)
+        .ok()
+        .and_then(|len| usize::try_from(start).ok()?.checked_add(len))
+        .filter(|&end| end <= isize::MAX as usize)
+        .ok_or(CursorError::SizeOverflow)?;
+
+    if end > src.get_ref().as_ref().len() {
+        return Err(CursorError::Incomplete);
+    }
+
+    src.set_position(end as u64);
+    Ok(start as usize..end)
+}
+
+/// Read exactly `N` bytes from a cursor into an array, advancing the position
+/// to the next unread byte.
+///
+/// # Errors
+///
+/// If there aren't `N` bytes remaining, `CursorError::Incomplete` is returned
+/// and the cursor is not advanced.
+///
+/// # Examples
+///
+/// Reading from a slice successfully:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{array, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new(b"\x7fELF\x02");
+///
+/// let magic: Result<[u8; 4], CursorError> = array(&mut src);
+/// assert_eq!(magic, Ok(*b"\x7fELF"));
+/// ```
+/// Trying to read from a slice that's incomplete:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{array, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new(b"\x7fEL");
+///
+/// assert_eq!(array::<4>(&mut src), Err(CursorError::Incomplete));
+/// assert_eq!(src.position(), 0);
+/// ```
+pub fn array<const N: usize>(src: &mut Cursor<impl AsRef<[u8]>>) -> Result<[u8; N], CursorError> {
+    let range = slice_range(src, N as u64)?;
+    Ok(src.get_ref().as_ref()[range]
+        .try_into()
+        .expect("slice has length N"))
+}
+
+macro_rules! binary_int {
+    ($($name:ident: $ty:ty = $from:ident / $to:ident, $endian:literal;)*) => {$(
+        #[doc = concat!("Read a ", $endian, " `", stringify!($ty), "` from a cursor, advancing")]
+        #[doc = concat!("the position by `size_of::<", stringify!($ty), ">()` bytes.")]
+        ///
+        /// # Errors
+        ///
+        /// If there aren't enough bytes remaining, `CursorError::Incomplete` is returned
+        /// and the cursor is not advanced.
+        ///
+        /// # Examples
+        /// ```
+        /// # use std::io::Cursor;
+        #[doc = concat!("# use cursor::{", stringify!($name), ", CursorError};")]
+        #[doc = concat!("let bytes = 42", stringify!($ty), ".", stringify!($to), "();")]
+        /// let mut src: Cursor<&[u8]> = Cursor::new(&bytes);
+        ///
+        #[doc = concat!("assert_eq!(", stringify!($name), "(&mut src), Ok(42));")]
+        #[doc = concat!("assert_eq!(", stringify!($name), "(&mut src), Err(CursorError::Incomplete));")]
+        /// ```
+        pub fn $name(src: &mut Cursor<impl AsRef<[u8]>>) -> Result<$ty, CursorError> {
+            array(src).map(<$ty>::$from)
+        }
+    )*};
+}
+
+binary_int! {
+    u16_be: u16 = from_be_bytes / to_be_bytes, "big-endian";
+    u16_le: u16 = from_le_bytes / to_le_bytes, "little-endian";
+    u32_be: u32 = from_be_bytes / to_be_bytes, "big-endian";
+    u32_le: u32 = from_le_bytes / to_le_bytes, "little-endian";
+    u64_be: u64 = from_be_bytes / to_be_bytes, "big-endian";
+    u64_le: u64 = from_le_bytes / to_le_bytes, "little-endian";
+    i64_be: i64 = from_be_bytes / to_be_bytes, "big-endian";
+    i64_le: i64 = from_le_bytes / to_le_bytes, "little-endian";
+}
+
+/// Read a size-prefixed payload from a cursor: an ASCII-encoded, `\r\n`-terminated
+/// decimal size, then exactly that many bytes, then `\r\n`. The position is
+/// advanced just past the final `\n`.
+///
+/// # Errors
+///
+/// If any part of the payload hasn't arrived yet, then `CursorError::Unterminated`
+/// or `CursorError::Incomplete` is returned and the cursor is not advanced at all.
+///
+/// If the size can't be parsed, the same errors as [`size`] are returned, and if
+/// it's too large to ever fit in memory, `CursorError::SizeOverflow` is
+/// returned. If the payload isn't followed by `\r\n`, `CursorError::MissingCrlf`
+/// is returned. In these cases the cursor is left just past the bytes that were
+/// read.
+///
+/// # Examples
+///
+/// Reading from a slice successfully:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{bulk, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("5\r\nHello\r\n".as_bytes());
+///
+/// let result: Result<&[u8], CursorError> = bulk(&mut src);
+/// assert_eq!(result, Ok("Hello".as_bytes()));
+/// ```
+/// Trying to read from a slice that's incomplete:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{bulk, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("5\r\nHello\r".as_bytes());
+///
+/// assert_eq!(bulk(&mut src), Err(CursorError::Incomplete));
+/// assert_eq!(src.position(), 0);
+/// ```
+/// Trying to read a payload with no trailing `\r\n`:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{bulk, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("5\r\nHello!!".as_bytes());
+///
+/// assert_eq!(bulk(&mut src), Err(CursorError::MissingCrlf));
+/// ```
+/// Trying to read a payload whose size can't fit in memory:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{bulk, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("18446744073709551615\r\nabc".as_bytes());
+/// assert_eq!(bulk(&mut src), Err(CursorError::SizeOverflow));
+///
+/// let mut src: Cursor<&[u8]> = Cursor::new("9223372036854775807\r\nabc".as_bytes());
+/// assert_eq!(bulk(&mut src), Err(CursorError::SizeOverflow));
+/// ```
+pub fn bulk<'buf>(
+    src: &mut Cursor<&'buf (impl AsRef<[u8]> + ?Sized)>,
+) -> Result<&'buf [u8], CursorError> {
+    with_rollback(src, |src| {
+        let len = size(src)?;
+        let payload = slice(src, len)?;
+        crlf(src)?;
+        Ok(payload)
+    })
+}
+
+/// Read a [netstring](https://cr.yp.to/proto/netstrings.txt) from a cursor,
+/// which is a decimal size, then `:`, then exactly that many bytes, then `,`.
+/// The position is advanced just past the `,`.
+///
+/// # Errors
+///
+/// If any part of the netstring hasn't arrived yet, then `CursorError::Incomplete`
+/// is returned and the cursor is not advanced at all.
+///
+/// If the size isn't all digits followed by `:`, `CursorError::SizeInvalidDigit`
+/// is returned, or `CursorError::SizeOverflow` if it doesn't fit in a `u64` or is
+/// too large to ever fit in memory. If the payload isn't followed by `,`,
+/// `CursorError::Mismatch` is returned. In both cases the cursor is left just
+/// past the bytes that were read.
+///
+/// # Examples
+///
+/// Reading from a slice successfully:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{netstring, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("5:hello,0:,".as_bytes());
+///
+/// assert_eq!(netstring(&mut src), Ok("hello".as_bytes()));
+/// assert_eq!(netstring(&mut src), Ok("".as_bytes()));
+/// ```
+/// Trying to read from a slice that's incomplete:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{netstring, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("5:hello".as_bytes());
+///
+/// assert_eq!(netstring(&mut src), Err(CursorError::Incomplete));
+/// assert_eq!(src.position(), 0);
+/// ```
+/// Trying to read malformed netstrings:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{netstring, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("5;hello,".as_bytes());
+/// assert_eq!(netstring(&mut src), Err(CursorError::SizeInvalidDigit));
+///
+/// let mut src: Cursor<&[u8]> = Cursor::new("5:hello!".as_bytes());
+/// assert_eq!(netstring(&mut src), Err(CursorError::Mismatch));
+///
+/// let mut src: Cursor<&[u8]> = Cursor::new("18446744073709551615:abc".as_bytes());
+/// assert_eq!(netstring(&mut src), Err(CursorError::SizeOverflow));
+///
+/// let mut src: Cursor<&[u8]> = Cursor::new("9223372036854775808:abc".as_bytes());
+/// assert_eq!(netstring(&mut src), Err(CursorError::SizeOverflow));
+/// ```
+pub fn netstring<'buf>(
+    src: &mut Cursor<&'buf (impl AsRef<[u8]> + ?Sized)>,
+) -> Result<&'buf [u8], CursorError> {
+    with_rollback(src, |src| {
+        let len = parse_size(take_while(src, |b| b.is_ascii_digit())?)?;
+        expect(src, b":").map_err(|err| match err {
+            CursorError::Mismatch => CursorError::SizeInvalidDigit,
+            err => err,
+        })?;
+        let payload = slice(src, len)?;
+        expect(src, b",")?;
+        Ok(payload)
+    })
+}
+
+/// Read a payload prefixed by its length as a big-endian `u32` from a cursor,
+/// advancing the position just past the payload.
+///
+/// # Errors
+///
+/// If the length or any of the payload hasn't arrived yet, then
+/// `CursorError::Incomplete` is returned and the cursor is not advanced at all.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{length_prefixed, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new(b"\0\0\0\x05hello\0\0\0\x05hel".as_slice());
+///
+/// assert_eq!(length_prefixed(&mut src), Ok("hello".as_bytes()));
+/// assert_eq!(length_prefixed(&mut src), Err(CursorError::Incomplete));
+/// assert_eq!(src.position(), 9);
+/// ```
+pub fn length_prefixed<'buf>(
+    src: &mut Cursor<&'buf (impl AsRef<[u8]> + ?Sized)>,
+) -> Result<&'buf [u8], CursorError> {
+    length_prefixed_bounded(src, u32::MAX)
+}
+
+/// Read a payload prefixed by its length as a big-endian `u32` from a cursor
+/// like [`length_prefixed`], but only if the length is at most `max_len`.
+///
+/// This rejects an oversized frame as soon as its length arrives, instead of
+/// waiting for a payload that may never fit in the buffer.
+///
+/// # Errors
+///
+/// If the length is more than `max_len`, `CursorError::FrameTooLarge` is
+/// returned and the cursor is not advanced. Otherwise the same errors as
+/// [`length_prefixed`] are returned.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{length_prefixed_bounded, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new(b"\xff\xff\xff\xff".as_slice());
+///
+/// assert_eq!(length_prefixed_bounded(&mut src, 1024), Err(CursorError::FrameTooLarge));
+/// assert_eq!(src.position(), 0);
+/// ```
+pub fn length_prefixed_bounded<'buf>(
+    src: &mut Cursor<&'buf (impl AsRef<[u8]> + ?Sized)>,
+    max_len: u32,
+) -> Result<&'buf [u8], CursorError> {
+    with_rollback(src, |src| {
+        let len = u32::from_be_bytes(array(src)?);
+        if len > max_len {
+            // `with_rollback` keeps errors like this consumed, so leave the prefix unread here
+            src.set_position(src.position() - 4);
+            return Err(CursorError::FrameTooLarge);
+        }
+        slice(src, u64::from(len))
+    })
+}
+
+/// Read an unsigned LEB128 varint from a cursor, advancing the position just
+/// past the last byte of the varint.
+///
+/// # Errors
+///
+/// If the buffer ends before a byte without the continuation bit is found,
+/// `CursorError::Incomplete` is returned and the cursor is not advanced.
+///
+/// If the encoding is longer than 10 bytes or the value doesn't fit in a `u64`,
+/// `CursorError::Varint` is returned and the cursor is not advanced.
+///
+/// # Examples
+///
+/// Reading from a slice successfully:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{varint_u64, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new(&[0x01, 0xAC, 0x02]);
+///
+/// assert_eq!(varint_u64(&mut src), Ok(1));
+/// assert_eq!(varint_u64(&mut src), Ok(300));
+/// ```
+/// Trying to read from a slice that's incomplete:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{varint_u64, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new(&[0xAC]);
+///
+/// assert_eq!(varint_u64(&mut src), Err(CursorError::Incomplete));
+/// assert_eq!(src.position(), 0);
+/// ```
+/// Trying to read a varint that overflows:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{varint_u64, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new(&[0xFF; 11]);
+///
+/// assert_eq!(varint_u64(&mut src), Err(CursorError::Varint));
+/// ```
+pub fn varint_u64(src: &mut Cursor<impl AsRef<[u8]>>) -> Result<u64, CursorError> {
+    let rem = rest(src);
+
+    let mut value: u64 = 0;
+    for (i, &b) in rem.iter().enumerate() {
+        if i == 9 && b > 1 {
+            return Err(CursorError::Varint);
+        }
+        value |= u64::from(b & 0x7F) << (7 * i);
+        if b & 0x80 == 0 {
+            src.set_position(src.position() + i as u64 + 1);
+            return Ok(value);
+        }
+    }
+
+    Err(CursorError::Incomplete)
+}
+
+/// Read a zigzag-encoded signed LEB128 varint from a cursor, advancing the
+/// position just past the last byte of the varint.
+///
+/// # Errors
+///
+/// This function fails in the same ways as [`varint_u64`].
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{varint_i64, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new(&[0x00, 0x01, 0x02, 0x03]);
+///
+/// assert_eq!(varint_i64(&mut src), Ok(0));
+/// assert_eq!(varint_i64(&mut src), Ok(-1));
+/// assert_eq!(varint_i64(&mut src), Ok(1));
+/// assert_eq!(varint_i64(&mut src), Ok(-2));
+/// ```
+pub fn varint_i64(src: &mut Cursor<impl AsRef<[u8]>>) -> Result<i64, CursorError> {
+    let n = varint_u64(src)?;
+    Ok((n >> 1) as i64 ^ -((n & 1) as i64))
+}
+
+/// Read a double-quoted string from a cursor, advancing the position just
+/// past the closing `"`.
+///
+/// The escapes `\"`, `\\`, `\n`, `\r`, `\t`, and `\x` followed by two hex
+/// digits are recognized. If there are no escapes, the string is borrowed
+/// from the buffer, and otherwise the unescaped bytes are returned.
+///
+/// # Errors
+///
+/// If the buffer ends before the closing `"`, then `CursorError::Incomplete`
+/// is returned. If there's a `\r` or `\n` before the closing `"`, then
+/// `CursorError::UnterminatedQuote` is returned. If there's an escape that
+/// isn't recognized, then `CursorError::BadEscape` is returned. If the cursor
+/// doesn't start with `"`, then `CursorError::Mismatch` is returned. In all
+/// cases the cursor is not advanced.
+///
+/// # Examples
+/// ```
+/// # use std::borrow::Cow;
+/// # use std::io::Cursor;
+/// # use cursor::{quoted_string, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new(r#""my key" "a\"b\x21""#.as_bytes());
+///
+/// assert!(matches!(quoted_string(&mut src), Ok(Cow::Borrowed(b"my key"))));
+/// src.set_position(src.position() + 1);
+/// assert_eq!(quoted_string(&mut src).unwrap(), &b"a\"b!"[..]);
+///
+/// let mut src: Cursor<&[u8]> = Cursor::new("\"oops\r\n".as_bytes());
+/// assert_eq!(quoted_string(&mut src), Err(CursorError::UnterminatedQuote));
+///
+/// let mut src: Cursor<&[u8]> = Cursor::new(r#""\q""#.as_bytes());
+/// assert_eq!(quoted_string(&mut src), Err(CursorError::BadEscape));
+/// ```
+pub fn quoted_string<'buf>(
+    src: &mut Cursor<&'buf (impl AsRef<[u8]> + ?Sized)>,
+) -> Result<Cow<'buf, [u8]>, CursorError> {
+    let rem = &buf(src)[src.position() as usize..];
+    let (string, len) = scan_quoted(rem)?;
+    src.set_position(src.position() + len as u64);
+    Ok(string)
+}
+
+/// Unescapes the double-quoted string at the start of `bytes`, returning it
+/// along with the number of bytes it took up, including the quotes.
+pub(crate) fn scan_quoted(bytes: &[u8]) -> Result<(Cow<'_, [u8]>, usize), CursorError> {
+    match bytes.first() {
+        Some(b'"') => {}
+        Some(_) => return Err(CursorError::Mismatch),
+        None => return Err(CursorError::Incomplete),
+    }
+
+    let mut unescaped: Option<Vec<u8>> = None;
+    let mut start = 1;
+    let mut i = 1;
+    loop {
+        match *bytes.get(i).ok_or(CursorError::Incomplete)? {
+            b'"' => {
+                let string = match unescaped {
+                    Some(mut unescaped) => {
+                        unescaped.extend_from_slice(&bytes[start..i]);
+                        Cow::Owned(unescaped)
+                    }
+                    None => Cow::Borrowed(&bytes[start..i]),
+                };
+                return Ok((string, i + 1));
+            }
+            b'\r' | b'\n' => return Err(CursorError::UnterminatedQuote),
+            b'\\' => {
+                let (b, len) = match *bytes.get(i + 1).ok_or(CursorError::Incomplete)? {
+                    b'"' => (b'"', 2),
+                    b'\\' => (b'\\', 2),
+                    b'n' => (b'\n', 2),
+                    b'r' => (b'\r', 2),
+                    b't' => (b'\t', 2),
+                    b'x' => {
+                        let hex = bytes.get(i + 2..i + 4).ok_or(CursorError::Incomplete)?;
+                        let digit = |b: u8| (b as char).to_digit(16).ok_or(CursorError::BadEscape);
+                        ((digit(hex[0])? << 4 | digit(hex[1])?) as u8, 4)
+                    }
+                    _ => return Err(CursorError::BadEscape),
+                };
+                let unescaped = unescaped.get_or_insert_with(Vec::new);
+                unescaped.extend_from_slice(&bytes[start..i]);
+                unescaped.push(b);
+                i += len;
+                start = i;
+            }
+            _ => i += 1,
+        }
+    }
+}
+
+/// Read bytes from a cursor up to (but not including) the first `delim`,
+/// advancing the position to the `delim`.
+///
+/// # Errors
+///
+/// If there's no `delim` in the remaining bytes, then `CursorError::Unterminated`
+/// is returned and the cursor is not advanced.
+///
+/// # Examples
+///
+/// Reading from a slice successfully:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{byte, take_until, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("key=value".as_bytes());
+///
+/// assert_eq!(take_until(&mut src, b'='), Ok("key".as_bytes()));
+///
+/// // The delimiter is left in the cursor
+/// assert_eq!(byte(&mut src), Ok(b'='));
+/// ```
+/// Trying to read from a slice that's incomplete:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{take_until, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("key".as_bytes());
+///
+/// assert_eq!(take_until(&mut src, b'='), Err(CursorError::Unterminated(3)));
+/// ```
+pub fn take_until<'buf>(
+    src: &mut Cursor<&'buf (impl AsRef<[u8]> + ?Sized)>,
+    delim: u8,
+) -> Result<&'buf [u8], CursorError> {
+    take_while(src, |b| b != delim).map_err(|_| CursorError::Unterminated(rest(src).len()))
+}
+
+/// Read bytes from a cursor for as long as `pred` returns `true`, advancing
+/// the position to the first byte that doesn't match.
+///
+/// # Errors
+///
+/// If every remaining byte matches, then more matching bytes may still be
+/// on the way, so `CursorError::Incomplete` is returned and the cursor is
+/// not advanced.
+///
+/// # Examples
+///
+/// Reading from a slice successfully:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{take_while, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("123abc".as_bytes());
+///
+/// let result = take_while(&mut src, |b| b.is_ascii_digit());
+/// assert_eq!(result, Ok("123".as_bytes()));
+/// assert_eq!(src.position(), 3);
+/// ```
+/// Trying to read from a slice that's incomplete:
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{take_while, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("123".as_bytes());
+///
+/// let result = take_while(&mut src, |b| b.is_ascii_digit());
+/// assert_eq!(result, Err(CursorError::Incomplete));
+/// ```
+pub fn take_while<'buf>(
+    src: &mut Cursor<&'buf (impl AsRef<[u8]> + ?Sized)>,
+    pred: impl Fn(u8) -> bool,
+) -> Result<&'buf [u8], CursorError> {
+    let rem = &buf(src)[src.position() as usize..];
+
+    let index = rem
+        .iter()
+        .position(|&b| !pred(b))
+        .ok_or(CursorError::Incomplete)?;
+
+    src.set_position(src.position() + index as u64);
+    Ok(&rem[..index])
+}
+
+/// Skip `len` bytes in a cursor, advancing the position past them.
+///
+/// # Errors
+///
+/// If there aren't `len` bytes remaining, `CursorError::Incomplete` is returned
+/// and the cursor is not advanced.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{skip, slice, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("\0\0\0\0data".as_bytes());
+///
+/// assert_eq!(skip(&mut src, 4), Ok(()));
+/// assert_eq!(slice(&mut src, 4), Ok("data".as_bytes()));
+///
+/// assert_eq!(skip(&mut src, 1), Err(CursorError::Incomplete));
+/// ```
+pub fn skip(src: &mut Cursor<impl AsRef<[u8]>>, len: u64) -> Result<(), CursorError> {
+    slice_range(src, len).map(|_| ())
+}
+
+/// Skip a `\r\n`-terminated line in a cursor, advancing the position just
+/// past the `\n`.
+///
+/// # Errors
+///
+/// If there's no terminating `\r\n`, then `CursorError::Unterminated` is returned
+/// and the cursor is not advanced.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{line, skip_line, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("ignored\r\nkept\r\n".as_bytes());
+///
+/// assert_eq!(skip_line(&mut src), Ok(()));
+/// assert_eq!(line(&mut src), Ok("kept".as_bytes()));
+/// ```
+pub fn skip_line(src: &mut Cursor<impl AsRef<[u8]>>) -> Result<(), CursorError> {
+    line_range(src, b"\r\n").map(|_| ())
+}
+
+/// Discard everything up to and including the next `\r\n` in a cursor,
+/// returning the number of bytes discarded.
+///
+/// This is for resynchronizing after a parse failure: whatever made the line
+/// malformed, the next line starts after its `\r\n`.
+///
+/// # Errors
+///
+/// If there's no `\r\n`, then `CursorError::Unterminated` is returned with the
+/// number of bytes discarded. Unlike other functions in this crate,
+/// the cursor is still advanced, since the bytes are garbage anyways, but a
+/// trailing `\r` is kept in case the `\n` comes next. Calling this again once
+/// there's more data continues discarding the same line.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{integer, line, recover_to_next_line, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("oops\r\n+OK\r\n".as_bytes());
+///
+/// assert_eq!(integer(&mut src), Err(CursorError::InvalidDigit));
+/// // Go back to the start of the bad frame, then skip it
+/// src.set_position(0);
+/// assert_eq!(recover_to_next_line(&mut src), Ok(6));
+/// assert_eq!(line(&mut src), Ok("+OK".as_bytes()));
+///
+/// let mut src: Cursor<&[u8]> = Cursor::new("garbage\r".as_bytes());
+/// assert_eq!(recover_to_next_line(&mut src), Err(CursorError::Unterminated(7)));
+/// assert_eq!(src.position(), 7);
+/// ```
+pub fn recover_to_next_line(src: &mut Cursor<impl AsRef<[u8]>>) -> Result<usize, CursorError> {
+    let rem = rest(src);
+    let (len, result) = match find(rem, b"\r\n") {
+        Some(i) => (i + 2, Ok(i + 2)),
+        None => {
+            let len = rem.len() - usize::from(rem.ends_with(b"\r"));
+            (len, Err(CursorError::Unterminated(len)))
+        }
+    };
+
+    src.set_position(src.position() + len as u64);
+    result
+}
+
+/// Skip any spaces and tabs at the front of a cursor, advancing the position
+/// to the first byte that is neither.
+///
+/// This never fails: if the cursor runs out of bytes, the next read will
+/// report that there isn't enough data.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{line, skip_whitespace, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new(" \t value\r\n".as_bytes());
+///
+/// skip_whitespace(&mut src);
+/// assert_eq!(line(&mut src), Ok("value".as_bytes()));
+/// ```
+pub fn skip_whitespace(src: &mut Cursor<impl AsRef<[u8]>>) {
+    let rem = rest(src);
+
+    let len = rem
+        .iter()
+        .position(|&b| b != b' ' && b != b'\t')
+        .unwrap_or(rem.len());
+
+    src.set_position(src.position() + len as u64);
+}
+
+/// Returns the number of bytes between the position of a cursor and the end
+/// of its buffer.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{byte, remaining};
+/// let mut src: Cursor<&[u8]> = Cursor::new(&[1, 2, 3]);
+/// assert_eq!(remaining(&src), 3);
+///
+/// byte(&mut src).unwrap();
+/// assert_eq!(remaining(&src), 2);
+/// ```
+pub fn remaining(src: &Cursor<impl AsRef<[u8]>>) -> usize {
+    src.get_ref()
+        .as_ref()
+        .len()
+        .saturating_sub(src.position() as usize)
+}
+
+/// Returns whether there are any bytes between the position of a cursor and the
+/// end of its buffer.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{byte, has_remaining};
+/// let mut src: Cursor<&[u8]> = Cursor::new(&[1]);
+/// assert!(has_remaining(&src));
+///
+/// byte(&mut src).unwrap();
+/// assert!(!has_remaining(&src));
+/// ```
+pub fn has_remaining(src: &Cursor<impl AsRef<[u8]>>) -> bool {
+    remaining(src) > 0
+}
+
+/// Advance the position of a cursor by `len` bytes.
+///
+/// This is the same as [`skip`], named to match [`bytes::Buf::advance`].
+///
+/// [`bytes::Buf::advance`]: https://docs.rs/bytes/latest/bytes/trait.Buf.html#tymethod.advance
+///
+/// # Errors
+///
+/// If there aren't `len` bytes remaining, `CursorError::Incomplete` is returned
+/// and the cursor is not advanced.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{advance, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new(&[1, 2, 3]);
+///
+/// assert_eq!(advance(&mut src, 2), Ok(()));
+/// assert_eq!(advance(&mut src, 2), Err(CursorError::Incomplete));
+/// assert_eq!(src.position(), 2);
+/// ```
+pub fn advance(src: &mut Cursor<impl AsRef<[u8]>>, len: u64) -> Result<(), CursorError> {
+    skip(src, len)
+}
+
+/// Consume `tag` from the front of a cursor, advancing the position past it.
+///
+/// # Errors
+///
+/// If the cursor doesn't start with `tag`, `CursorError::Mismatch` is returned.
+/// If the remaining bytes are a strict prefix of `tag`, then it could still
+/// match once more data arrives, so `CursorError::Incomplete` is returned.
+/// In both cases the cursor is not advanced.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{expect, line, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("GET /index.html\r\n".as_bytes());
+///
+/// assert_eq!(expect(&mut src, b"SET "), Err(CursorError::Mismatch));
+/// assert_eq!(expect(&mut src, b"GET "), Ok(()));
+/// assert_eq!(line(&mut src), Ok("/index.html".as_bytes()));
+///
+/// let mut src: Cursor<&[u8]> = Cursor::new("GE".as_bytes());
+/// assert_eq!(expect(&mut src, b"GET "), Err(CursorError::Incomplete));
+/// ```
+pub fn expect(src: &mut Cursor<impl AsRef<[u8]>>, tag: &[u8]) -> Result<(), CursorError> {
+    expect_by(src, tag, |a, b| a == b)
+}
+
+/// Like [`expect`], but ignoring ASCII case differences.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{expect_ignore_case, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("get key".as_bytes());
+///
+/// assert_eq!(expect_ignore_case(&mut src, b"GET "), Ok(()));
+/// assert_eq!(src.position(), 4);
+/// ```
+pub fn expect_ignore_case(
+    src: &mut Cursor<impl AsRef<[u8]>>,
+    tag: &[u8],
+) -> Result<(), CursorError> {
+    expect_by(src, tag, |a, b| a.eq_ignore_ascii_case(b))
+}
+
+/// Consume a `\r\n` from the front of a cursor, advancing the position past it.
+///
+/// # Errors
+///
+/// If the cursor doesn't start with `\r\n`, `CursorError::MissingCrlf` is
+/// returned. If there aren't enough bytes to tell yet, `CursorError::Incomplete`
+/// is returned. In both cases the cursor is not advanced.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{crlf, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new("\r\nx".as_bytes());
+///
+/// assert_eq!(crlf(&mut src), Ok(()));
+/// assert_eq!(crlf(&mut src), Err(CursorError::MissingCrlf));
+/// ```
+pub fn crlf(src: &mut Cursor<impl AsRef<[u8]>>) -> Result<(), CursorError> {
+    expect(src, b"\r\n").map_err(|err| match err {
+        CursorError::Mismatch => CursorError::MissingCrlf,
+        err => err,
+    })
+}
+
+fn expect_by(
+    src: &mut Cursor<impl AsRef<[u8]>>,
+    tag: &[u8],
+    eq: impl Fn(&[u8], &[u8]) -> bool,
+) -> Result<(), CursorError> {
+    let rem = rest(src);
+    let len = rem.len().min(tag.len());
+    if !eq(&rem[..len], &tag[..len]) {
+        return Err(CursorError::Mismatch);
+    }
+    if len < tag.len() {
+        return Err(CursorError::Incomplete);
+    }
+
+    src.set_position(src.position() + len as u64);
+    Ok(())
+}
+
+/// Read a byte from a cursor without moving the position.
+///
+/// # Errors
+///
+/// If the cursor is at the end of the buffer, `CursorError::Incomplete`
+/// is returned instead.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{byte, peek_byte, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new(&[1]);
+///
+/// // Peeking doesn't advance the cursor
+/// assert_eq!(peek_byte(&src), Ok(1));
+/// assert_eq!(peek_byte(&src), Ok(1));
+///
+/// assert_eq!(byte(&mut src), Ok(1));
+/// assert_eq!(peek_byte(&src), Err(CursorError::Incomplete));
+/// ```
+pub fn peek_byte(src: &Cursor<impl AsRef<[u8]>>) -> Result<u8, CursorError> {
+    src.get_ref()
+        .as_ref()
+        .get(src.position() as usize)
+        .copied()
+        .ok_or(CursorError::Incomplete)
+}
+
+/// Read a `\r\n`-terminated line from a cursor without moving the position.
+///
+/// # Errors
+///
+/// If there's no terminating `\r\n`, then `CursorError::Unterminated` is returned.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{peek_line, CursorError};
+/// let src: Cursor<&[u8]> = Cursor::new("+OK\r\n".as_bytes());
+///
+/// assert_eq!(peek_line(&src), Ok("+OK".as_bytes()));
+/// assert_eq!(src.position(), 0);
+/// ```
+pub fn peek_line<'buf>(
+    src: &Cursor<&'buf (impl AsRef<[u8]> + ?Sized)>,
+) -> Result<&'buf [u8], CursorError> {
+    line(&mut src.clone())
+}
+
+/// Read `len` bytes from a cursor without moving the position.
+///
+/// # Errors
+///
+/// If there aren't `len` bytes remaining, `CursorError::Incomplete` is returned.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{peek_slice, CursorError};
+/// let src: Cursor<&[u8]> = Cursor::new("Hello, world!".as_bytes());
+///
+/// assert_eq!(peek_slice(&src, 5), Ok("Hello".as_bytes()));
+/// assert_eq!(src.position(), 0);
+///
+/// assert_eq!(peek_slice(&src, 20), Err(CursorError::Incomplete));
+/// ```
+pub fn peek_slice<'buf>(
+    src: &Cursor<&'buf (impl AsRef<[u8]> + ?Sized)>,
+    len: u64,
+) -> Result<&'buf [u8], CursorError> {
+    slice(&mut src.clone(), len)
+}
+
+/// Run a multi-step parse on a cursor, rolling the position back to where it
+/// started if the parse fails because there isn't enough data yet.
+///
+/// If `parse` fails for any other reason, or succeeds, the position is left
+/// wherever `parse` moved it.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{size, slice, with_rollback, CursorError};
+/// // A size line, but not all of the payload has arrived yet
+/// let mut src: Cursor<&[u8]> = Cursor::new("5\r\nHel".as_bytes());
+///
+/// let result = with_rollback(&mut src, |src| {
+///     let len = size(src)?;
+///     slice(src, len)
+/// });
+/// assert_eq!(result, Err(CursorError::Incomplete));
+///
+/// // The size line was un-read
+/// assert_eq!(src.position(), 0);
+/// ```
+pub fn with_rollback<B, T>(
+    src: &mut Cursor<B>,
+    parse: impl FnOnce(&mut Cursor<B>) -> Result<T, CursorError>,
+) -> Result<T, CursorError> {
+    let pos = src.position();
+    let result = parse(src);
+    if matches!(&result, Err(err) if err.not_enough_data()) {
+        src.set_position(pos);
+    }
+    result
+}
+
+/// Run a parse on a cursor, attaching the starting position and a snippet of
+/// the input to any error.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{integer, line, with_context, CursorError};
+/// let mut src: Cursor<&[u8]> = Cursor::new(":1\r\n:x\r\n".as_bytes());
+///
+/// let parse = |src: &mut Cursor<&[u8]>| {
+///     line(src)?;
+///     integer(src)
+/// };
+/// let err = with_context(&mut src, parse).unwrap_err();
+///
+/// assert_eq!(err.kind(), CursorError::InvalidDigit);
+/// assert_eq!(err.offset(), 0);
+/// assert_eq!(err.snippet(), b":1\r\n:x\r\n");
+/// assert_eq!(
+///     err.to_string(),
+///     r#"invalid digit in integer at line 1, column 1 (byte 0, near ":1\r\n:x\r\n")"#,
+/// );
+///
+/// // The position of an error on a later line
+/// let mut src: Cursor<&[u8]> = Cursor::new("workers 4\r\nthreads x\r\n".as_bytes());
+/// cursor::line(&mut src).unwrap();
+/// let parse = |src: &mut Cursor<&[u8]>| {
+///     cursor::skip(src, 8)?;
+///     integer(src)
+/// };
+/// let err = with_context(&mut src, parse).unwrap_err();
+/// assert_eq!((err.line(), err.column()), (2, 1));
+/// ```
+pub fn with_context<B: AsRef<[u8]>, T>(
+    src: &mut Cursor<B>,
+    parse: impl FnOnce(&mut Cursor<B>) -> Result<T, CursorError>,
+) -> Result<T, CursorErrorAt> {
+    let pos = src.position();
+    parse(src).map_err(|kind| CursorErrorAt::new(kind, src.get_ref().as_ref(), pos))
+}
+
+/// Returns the index of the first occurrence of `needle` in `haystack`.
+#[cfg(feature = "memchr")]
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    memchr::memmem::find(haystack, needle)
+}
+
+/// Returns the index of the first occurrence of `needle` in `haystack`.
+#[cfg(not(feature = "memchr"))]
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Returns the whole buffer of a cursor over a borrowed buffer.
+fn buf<'buf>(src: &Cursor<&'buf (impl AsRef<[u8]> + ?Sized)>) -> &'buf [u8] {
+    (*src.get_ref()).as_ref()
+}
+
+/// Returns the bytes from the position of a cursor to the end of its buffer.
+fn rest<T: AsRef<[u8]>>(src: &Cursor<T>) -> &[u8] {
+    src.get_ref()
+        .as_ref()
+        .get(src.position() as usize..)
+        .expect("position in bounds")
+}
+
+/// Extension trait for calling the parsing functions in this crate as methods
+/// on a [`Cursor<&[u8]>`](Cursor).
+///
+/// Each method behaves exactly like the free function of the same name.
+///
+/// # Examples
+/// ```
+/// # use std::io::Cursor;
+/// # use cursor::{CursorError, CursorExt};
+/// let mut src: Cursor<&[u8]> = Cursor::new("*2\r\n100\r\n-7\r\nabc".as_bytes());
+///
+/// assert_eq!(src.byte(), Ok(b'*'));
+/// assert_eq!(src.line(), Ok("2".as_bytes()));
+/// assert_eq!(src.size(), Ok(100));
+/// assert_eq!(src.integer(), Ok(-7));
+/// assert_eq!(src.slice(3), Ok("abc".as_bytes()));
+/// assert_eq!(src.byte(), Err(CursorError::Incomplete));
+/// ```
+pub trait CursorExt<'buf> {
+    /// See [`byte`].
+    fn byte(&mut self) -> Result<u8, CursorError>;
+
+    /// See [`size`].
+    fn size(&mut self) -> Result<u64, CursorError>;
+
+    /// See [`size_hex`].
+    fn size_hex(&mut self) -> Result<u64, CursorError>;
+
+    /// See [`number_radix`].
+    fn number_radix<T>(&mut self, radix: u32) -> Result<T, CursorError>
+    where
+        T: num_traits::Num;
+
+    /// See [`integer`].
+    fn integer(&mut self) -> Result<i64, CursorError>;
+
+    /// See [`integer_i128`].
+    fn integer_i128(&mut self) -> Result<i128, CursorError>;
+
+    /// See [`size_u128`].
+    fn size_u128(&mut self) -> Result<u128, CursorError>;
+
+    /// See [`number`].
+    fn number<T>(&mut self) -> Result<T, CursorError>
+    where
+        T: atoi::FromRadix10SignedChecked;
+
+    /// See [`double`].
+    fn double(&mut self) -> Result<f64, CursorError>;
+
+    /// See [`boolean`].
+    fn boolean(&mut self) -> Result<bool, CursorError>;
+
+    /// See [`line`].
+    fn line(&mut self) -> Result<&'buf [u8], CursorError>;
+
+    /// See [`line_bounded`].
+    fn line_bounded(&mut self, max_len: usize) -> Result<&'buf [u8], CursorError>;
+
+    /// See [`str_line`].
+    fn str_line(&mut self) -> Result<&'buf str, CursorError>;
+
+    /// See [`line_hex`].
+    fn line_hex(&mut self) -> Result<Vec<u8>, CursorError>;
+
+    /// See [`line_base64`].
+    #[cfg(feature = "base64")]
+    fn line_base64(&mut self) -> Result<Vec<u8>, CursorError>;
+
+    /// See [`line_with`].
+    fn line_with(&mut self, terminator: &[u8]) -> Result<&'buf [u8], CursorError>;
+
+    /// See [`words`].
+    fn words(&mut self) -> Result<Fields<'buf>, CursorError>;
+
+    /// See [`slice()`].
+    fn slice(&mut self, len: u64) -> Result<&'buf [u8], CursorError>;
+
+    /// See [`array`].
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], CursorError>;
+
+    /// See [`u16_be`].
+    fn u16_be(&mut self) -> Result<u16, CursorError>;
+
+    /// See [`u16_le`].
+    fn u16_le(&mut self) -> Result<u16, CursorError>;
+
+    /// See [`u32_be`].
+    fn u32_be(&mut self) -> Result<u32, CursorError>;
+
+    /// See [`u32_le`].
+    fn u32_le(&mut self) -> Result<u32, CursorError>;
+
+    /// See [`u64_be`].
+    fn u64_be(&mut self) -> Result<u64, CursorError>;
+
+    /// See [`u64_le`].
+    fn u64_le(&mut self) -> Result<u64, CursorError>;
+
+    /// See [`i64_be`].
+    fn i64_be(&mut self) -> Result<i64, CursorError>;
+
+    /// See [`i64_le`].
+    fn i64_le(&mut self) -> Result<i64, CursorError>;
+
+    /// See [`bulk`].
+    fn bulk(&mut self) -> Result<&'buf [u8], CursorError>;
+
+    /// See [`netstring`].
+    fn netstring(&mut self) -> Result<&'buf [u8], CursorError>;
+
+    /// See [`length_prefixed`].
+    fn length_prefixed(&mut self) -> Result<&'buf [u8], CursorError>;
+
+    /// See [`length_prefixed_bounded`].
+    fn length_prefixed_bounded(&mut self, max_len: u32) -> Result<&'buf [u8], CursorError>;
+
+    /// See [`varint_u64`].
+    fn varint_u64(&mut self) -> Result<u64, CursorError>;
+
+    /// See [`varint_i64`].
+    fn varint_i64(&mut self) -> Result<i64, CursorError>;
+
+    /// See [`take_until`].
+    fn take_until(&mut self, delim: u8) -> Result<&'buf [u8], CursorError>;
+
+    /// See [`quoted_string`].
+    fn quoted_string(&mut self) -> Result<Cow<'buf, [u8]>, CursorError>;
+
+    /// See [`take_while`].
+    fn take_while(&mut self, pred: impl Fn(u8) -> bool) -> Result<&'buf [u8], CursorError>;
+
+    /// See [`skip`].
+    fn skip(&mut self, len: u64) -> Result<(), CursorError>;
+
+    /// See [`skip_line`].
+    fn skip_line(&mut self) -> Result<(), CursorError>;
+
+    /// See [`recover_to_next_line`].
+    fn recover_to_next_line(&mut self) -> Result<usize, CursorError>;
+
+    /// See [`skip_whitespace`].
+    fn skip_whitespace(&mut self);
+
+    /// See [`remaining`].
+    fn remaining(&self) -> usize;
+
+    /// See [`has_remaining`].
+    fn has_remaining(&self) -> bool;
+
+    /// See [`advance`].
+    fn advance(&mut self, len: u64) -> Result<(), CursorError>;
+
+    /// See [`expect`].
+    fn expect(&mut self, tag: &[u8]) -> Result<(), CursorError>;
+
+    /// See [`expect_ignore_case`].
+    fn expect_ignore_case(&mut self, tag: &[u8]) -> Result<(), CursorError>;
+
+    /// See [`crlf`].
+    fn crlf(&mut self) -> Result<(), CursorError>;
+
+    /// See [`peek_byte`].
+    fn peek_byte(&self) -> Result<u8, CursorError>;
+
+    /// See [`peek_line`].
+    fn peek_line(&self) -> Result<&'buf [u8], CursorError>;
+
+    /// See [`peek_slice`].
+    fn peek_slice(&self, len: u64) -> Result<&'buf [u8], CursorError>;
+}
+
+impl<'buf, B: AsRef<[u8]> + ?Sized> CursorExt<'buf> for Cursor<&'buf B> {
+    fn byte(&mut self) -> Result<u8, CursorError> {
+        byte(self)
+    }
+
+    fn size(&mut self) -> Result<u64, CursorError> {
+        size(self)
+    }
+
+    fn size_hex(&mut self) -> Result<u64, CursorError> {
+        size_hex(self)
+    }
+
+    fn number_radix<T>(&mut self, radix: u32) -> Result<T, CursorError>
+    where
+        T: num_traits::Num,
+    {
+        number_radix(self, radix)
+    }
+
+    fn integer(&mut self) -> Result<i64, CursorError> {
+        integer(self)
+    }
+
+    fn integer_i128(&mut self) -> Result<i128, CursorError> {
+        integer_i128(self)
+    }
+
+    fn size_u128(&mut self) -> Result<u128, CursorError> {
+        size_u128(self)
+    }
+
+    fn number<T>(&mut self) -> Result<T, CursorError>
+    where
+        T: atoi::FromRadix10SignedChecked,
+    {
+        number(self)
+    }
+
+    fn double(&mut self) -> Result<f64, CursorError> {
+        double(self)
+    }
+
+    fn boolean(&mut self) -> Result<bool, CursorError> {
+        boolean(self)
+    }
+
+    fn line(&mut self) -> Result<&'buf [u8], CursorError> {
+        line(self)
+    }
+
+    fn line_bounded(&mut self, max_len: usize) -> Result<&'buf [u8], CursorError> {
+        line_bounded(self, max_len)
+    }
+
+    fn str_line(&mut self) -> Result<&'buf str, CursorError> {
+        str_line(self)
+    }
+
+    fn line_hex(&mut self) -> Result<Vec<u8>, CursorError> {
+        line_hex(self)
+    }
+
+    #[cfg(feature = "base64")]
+    fn line_base64(&mut self) -> Result<Vec<u8>, CursorError> {
+        line_base64(self)
+    }
+
+    fn line_with(&mut self, terminator: &[u8]) -> Result<&'buf [u8], CursorError> {
+        line_with(self, terminator)
+    }
+
+    fn words(&mut self) -> Result<Fields<'buf>, CursorError> {
+        words(self)
+    }
+
+    fn slice(&mut self, len: u64) -> Result<&'buf [u8], CursorError> {
+        slice(self, len)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], CursorError> {
+        array(self)
+    }
+
+    fn u16_be(&mut self) -> Result<u16, CursorError> {
+        u16_be(self)
+    }
+
+    fn u16_le(&mut self) -> Result<u16, CursorError> {
+        u16_le(self)
+    }
+
+    fn u32_be(&mut self) -> Result<u32, CursorError> {
+        u32_be(self)
+    }
+
+    fn u32_le(&mut self) -> Result<u32, CursorError> {
+        u32_le(self)
+    }
+
+    fn u64_be(&mut self) -> Result<u64, CursorError> {
+        u64_be(self)
+    }
+
+    fn u64_le(&mut self) -> Result<u64, CursorError> {
+        u64_le(self)
+    }
+
+    fn i64_be(&mut self) -> Result<i64, CursorError> {
+        i64_be(self)
+    }
+
+    fn i64_le(&mut self) -> Result<i64, CursorError> {
+        i64_le(self)
+    }
+
+    fn bulk(&mut self) -> Result<&'buf [u8], CursorError> {
+        bulk(self)
+    }
+
+    fn netstring(&mut self) -> Result<&'buf [u8], CursorError> {
+        netstring(self)
+    }
+
+    fn length_prefixed(&mut self) -> Result<&'buf [u8], CursorError> {
+        length_prefixed(self)
+    }
+
+    fn length_prefixed_bounded(&mut self, max_len: u32) -> Result<&'buf [u8], CursorError> {
+        length_prefixed_bounded(self, max_len)
+    }
+
+    fn varint_u64(&mut self) -> Result<u64, CursorError> {
+        varint_u64(self)
+    }
+
+    fn varint_i64(&mut self) -> Result<i64, CursorError> {
+        varint_i64(self)
+    }
+
+    fn quoted_string(&mut self) -> Result<Cow<'buf, [u8]>, CursorError> {
+        quoted_string(self)
+    }
+
+    fn take_until(&mut self, delim: u8) -> Result<&'buf [u8], CursorError> {
+        take_until(self, delim)
+    }
+
+    fn take_while(&mut self, pred: impl Fn(u8) -> bool) -> Result<&'buf [u8], CursorError> {
+        take_while(self, pred)
+    }
+
+    fn skip(&mut self, len: u64) -> Result<(), CursorError> {
+        skip(self, len)
+    }
+
+    fn skip_line(&mut self) -> Result<(), CursorError> {
+        skip_line(self)
+    }
+
+    fn recover_to_next_line(&mut self) -> Result<usize, CursorError> {
+        recover_to_next_line(self)
+    }
+
+    fn skip_whitespace(&mut self) {
+        skip_whitespace(self)
+    }
+
+    fn remaining(&self) -> usize {
+        remaining(self)
+    }
+
+    fn has_remaining(&self) -> bool {
+        has_remaining(self)
+    }
+
+    fn advance(&mut self, len: u64) -> Result<(), CursorError> {
+        advance(self, len)
+    }
+
+    fn expect(&mut self, tag: &[u8]) -> Result<(), CursorError> {
+        expect(self, tag)
+    }
+
+    fn expect_ignore_case(&mut self, tag: &[u8]) -> Result<(), CursorError> {
+        expect_ignore_case(self, tag)
+    }
+
+    fn crlf(&mut self) -> Result<(), CursorError> {
+        crlf(self)
+    }
+
+    fn peek_byte(&self) -> Result<u8, CursorError> {
+        peek_byte(self)
+    }
+
+    fn peek_line(&self) -> Result<&'buf [u8], CursorError> {
+        peek_line(self)
+    }
+
+    fn peek_slice(&self, len: u64) -> Result<&'buf [u8], CursorError> {
+        peek_slice(self, len)
+    }
 }
@@ -0,0 +1,110 @@
+//! Decompressing a stream before it's buffered.
+
+use flate2::read::{GzDecoder, ZlibDecoder};
+use std::io::{self, Read};
+
+/// A content encoding negotiated for a stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// No compression.
+    Identity,
+    /// The gzip format.
+    Gzip,
+    /// The zlib format, which is what HTTP calls `deflate`.
+    Deflate,
+}
+
+/// A reader that decompresses the bytes of another reader.
+///
+/// Since [`ReadBuf::read`](crate::ReadBuf::read) accepts any [`Read`],
+/// wrapping a stream in a [`DecodingReader`] means the buffer only ever holds
+/// decompressed bytes, so frames can be parsed the same way no matter which
+/// encoding was negotiated.
+///
+/// If the encoding is negotiated partway through the stream, any bytes
+/// after the negotiation that were already read into a buffer have to be
+/// decompressed too, for example by chaining them in front of the stream with
+/// [`Read::chain`].
+///
+/// # Examples
+/// ```
+/// # use std::io::Write;
+/// # use flate2::write::GzEncoder;
+/// # use flate2::Compression;
+/// # use readbuf::compression::{DecodingReader, Encoding};
+/// # use readbuf::ReadBuf;
+/// let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+/// encoder.write_all(b"+OK\r\n:12\r\n").unwrap();
+/// let compressed = encoder.finish().unwrap();
+///
+/// let mut reader = DecodingReader::new(&compressed[..], Encoding::Gzip);
+/// let mut buf = ReadBuf::new();
+/// while buf.read(&mut reader).is_ok() {}
+///
+/// assert_eq!(buf.buf(), b"+OK\r\n:12\r\n");
+/// ```
+#[derive(Debug)]
+pub struct DecodingReader<R> {
+    inner: Inner<R>,
+}
+
+#[derive(Debug)]
+enum Inner<R> {
+    Identity(R),
+    Gzip(GzDecoder<R>),
+    Deflate(ZlibDecoder<R>),
+}
+
+impl<R: Read> DecodingReader<R> {
+    /// Creates a new [`DecodingReader`] that decompresses `reader` according
+    /// to `encoding`.
+    pub fn new(reader: R, encoding: Encoding) -> Self {
+        let inner = match encoding {
+            Encoding::Identity => Inner::Identity(reader),
+            Encoding::Gzip => Inner::Gzip(GzDecoder::new(reader)),
+            Encoding::Deflate => Inner::Deflate(ZlibDecoder::new(reader)),
+        };
+        DecodingReader { inner }
+    }
+}
+
+impl<R> DecodingReader<R> {
+    /// Returns the encoding being decompressed.
+    pub fn encoding(&self) -> Encoding {
+        match self.inner {
+            Inner::Identity(_) => Encoding::Identity,
+            Inner::Gzip(_) => Encoding::Gzip,
+            Inner::Deflate(_) => Encoding::Deflate,
+        }
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        match &self.inner {
+            Inner::Identity(reader) => reader,
+            Inner::Gzip(decoder) => decoder.get_ref(),
+            Inner::Deflate(decoder) => decoder.get_ref(),
+        }
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    ///
+    /// Reading from it directly will corrupt the compressed stream.
+    pub fn get_mut(&mut self) -> &mut R {
+        match &mut self.inner {
+            Inner::Identity(reader) => reader,
+            Inner::Gzip(decoder) => decoder.get_mut(),
+            Inner::Deflate(decoder) => decoder.get_mut(),
+        }
+    }
+}
+
+impl<R: Read> Read for DecodingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match &mut self.inner {
+            Inner::Identity(reader) => reader.read(buf),
+            Inner::Gzip(decoder) => decoder.read(buf),
+            Inner::Deflate(decoder) => decoder.read(buf),
+        }
+    }
+}
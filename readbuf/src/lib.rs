@@ -1,16 +1,82 @@
-use std::io::{self, Read};
+use cursor::frame::{Frame, FrameParser};
+use cursor::CursorError;
+use std::fmt;
+use std::io::{self, BufRead, Cursor, IoSliceMut, Read, Write};
+use std::net::TcpStream;
+use std::ops::{Deref, DerefMut};
 use std::ptr;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "bytes")]
+use bytes::{Bytes, BytesMut};
+
+/// Emits a `tracing` event at the given level when the `tracing` feature is
+/// enabled, and does nothing otherwise.
+macro_rules! event {
+    ($level:ident, $($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::$level!($($arg)*);
+    };
+}
+
+#[cfg(feature = "compression")]
+pub mod compression;
+pub mod pool;
+pub mod reader;
+pub mod ring;
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+pub mod runtime;
+pub mod write_buf;
+
+pub use reader::FrameReader;
+pub use write_buf::WriteBuf;
+
+/// The memory backing a [`ReadBuf`], which is shareable when the `bytes`
+/// feature is enabled so that [`ReadBuf::split_to`] doesn't need to copy.
+#[cfg(feature = "bytes")]
+type Storage = BytesMut;
+#[cfg(not(feature = "bytes"))]
+type Storage = Box<[u8]>;
+
+fn zeroed(len: usize) -> Storage {
+    #[cfg(feature = "bytes")]
+    return BytesMut::zeroed(len);
+    #[cfg(not(feature = "bytes"))]
+    return vec![0; len].into_boxed_slice();
+}
 
 /// Buffer for reading into.
 ///
 /// This type is very similar to [`BufReader`](std::io::BufReader), but with
 /// with the added ability to allow for users to request more data to be filled
 /// into the buffer even when the buffer isn't empty using [`ReadBuf::read`].
-#[derive(Debug, Default)]
+///
+/// If the buffer fills up with unconsumed bytes, it doubles in size on the
+/// next call to [`ReadBuf::read`], up to an optional cap set with
+/// [`ReadBuf::max_capacity`].
+///
+/// [`ReadBuf`] implements [`Read`] and [`BufRead`] over the bytes that are
+/// already buffered, and [`ReadBuf::attach`] gives a [`BufRead`] that pulls
+/// more bytes from a reader once those run out. Since the inherent
+/// [`ReadBuf::read`] takes precedence over [`Read::read`], the trait method
+/// has to be called as `Read::read(&mut buf, out)`.
+///
+/// For long-lived connections where compaction copies add up, see
+/// [`RingBuf`](ring::RingBuf).
+#[derive(Debug)]
 pub struct ReadBuf {
-    buf: Box<[u8]>,
+    buf: Storage,
     start: usize,
     end: usize,
+    max_capacity: Option<usize>,
+    compact_threshold: usize,
+    stats: Stats,
+}
+
+impl Default for ReadBuf {
+    fn default() -> Self {
+        Self::with_capacity(0)
+    }
 }
 
 impl ReadBuf {
@@ -22,49 +88,879 @@ impl ReadBuf {
     /// Creates a new [`ReadBuf`] with the given capacity.
     pub fn with_capacity(capacity: usize) -> Self {
         ReadBuf {
-            buf: vec![0; capacity].into_boxed_slice(),
+            buf: zeroed(capacity),
             start: 0,
             end: 0,
+            max_capacity: None,
+            compact_threshold: 512,
+            stats: Stats::default(),
         }
     }
 
+    /// Creates a new [`ReadBuf`] with an `initial` capacity that will never
+    /// grow past `max`.
+    ///
+    /// This is the same as `ReadBuf::with_capacity(initial).max_capacity(max)`,
+    /// except that `initial` is capped at `max`. Limiting how much a single
+    /// connection can buffer means a peer sending an enormous frame gets
+    /// [`ReadBufError::Full`] instead of exhausting memory.
+    ///
+    /// # Examples
+    /// ```
+    /// # use readbuf::{FrameReader, ReadBuf, ReadFrameError};
+    /// let frame = format!("${}\r\n{}\r\n", 100, "a".repeat(100));
+    /// let buf = ReadBuf::with_limits(16, 64);
+    /// let mut reader = FrameReader::with_buf(frame.as_bytes(), buf);
+    ///
+    /// assert!(matches!(reader.next_frame(), Err(ReadFrameError::Full)));
+    /// assert_eq!(reader.buf().capacity(), 64);
+    /// ```
+    pub fn with_limits(initial: usize, max: usize) -> Self {
+        Self::with_capacity(initial.min(max)).max_capacity(max)
+    }
+
+    /// Sets how much free space there must be at the end of the buffer before
+    /// [`ReadBuf::read`] stops compacting it first. The default is 512 bytes.
+    ///
+    /// A smaller threshold means fewer copies, but reads into a smaller
+    /// region. A threshold of 0 only compacts once the end is reached.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::io::Read;
+    /// # use readbuf::ReadBuf;
+    /// let mut reader = std::io::repeat(b'a');
+    ///
+    /// let mut buf = ReadBuf::with_capacity(16).compact_threshold(4);
+    /// buf.read((&mut reader).take(10)).unwrap();
+    /// buf.consume(5);
+    /// // 6 bytes are still free at the end, so the buffer isn't compacted
+    /// assert_eq!(buf.read(&mut reader).unwrap(), 6);
+    ///
+    /// let mut buf = ReadBuf::with_capacity(16).compact_threshold(8);
+    /// buf.read((&mut reader).take(10)).unwrap();
+    /// buf.consume(5);
+    /// // But with a higher threshold it is
+    /// assert_eq!(buf.read(&mut reader).unwrap(), 11);
+    /// ```
+    pub fn compact_threshold(mut self, compact_threshold: usize) -> Self {
+        self.compact_threshold = compact_threshold;
+        self
+    }
+
+    /// Sets the largest capacity the buffer is allowed to grow to.
+    ///
+    /// Once the buffer is full at this capacity, [`ReadBuf::read`] returns
+    /// [`ReadBufError::Full`] until some bytes are consumed.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::io;
+    /// # use readbuf::{ReadBuf, ReadBufError};
+    /// let mut buf = ReadBuf::with_capacity(4).max_capacity(8);
+    /// let mut reader = io::repeat(b'a');
+    ///
+    /// assert_eq!(buf.read(&mut reader).unwrap(), 4);
+    /// // The buffer is full, so it doubles in size
+    /// assert_eq!(buf.read(&mut reader).unwrap(), 4);
+    /// // It can't double again
+    /// assert!(matches!(buf.read(&mut reader), Err(ReadBufError::Full)));
+    ///
+    /// buf.consume(8);
+    /// assert_eq!(buf.read(&mut reader).unwrap(), 8);
+    /// ```
+    pub fn max_capacity(mut self, max_capacity: usize) -> Self {
+        self.max_capacity = Some(max_capacity);
+        self
+    }
+
     /// Reads some more bytes into the buffer, returning the number of bytes
     /// read.
     ///
     /// This method calls [`Read::read`] on the provided reader, but automatically
     /// provides a `&mut [u8]` for it to read into. The resulting buffer can
     /// then be accessed with [`ReadBuf::buf`].
-    pub fn read<R: Read>(&mut self, mut reader: R) -> io::Result<usize> {
-        if self.end + 512 > self.buf.len() {
-            // Remove garbage
-            // SAFETY: `self.start` and `self.end` are both valid indices into
-            // `self.buf`.
-            unsafe {
-                ptr::copy(
-                    self.buf.as_ptr().add(self.start),
-                    self.buf.as_mut_ptr(),
-                    self.end - self.start,
-                );
+    ///
+    /// If the buffer is full of unconsumed bytes, it's grown first so that a
+    /// frame larger than the current capacity can still be read in full.
+    ///
+    /// # Errors
+    ///
+    /// If the buffer is full and already at its [`ReadBuf::max_capacity`],
+    /// [`ReadBufError::Full`] is returned without reading. If the reader has
+    /// reached its end, [`ReadBufError::Eof`] is returned with the number of
+    /// bytes still buffered, so a clean close between frames can be told
+    /// apart from one partway through a frame. Errors from the reader are
+    /// returned as [`ReadBufError::Io`].
+    ///
+    /// # Examples
+    /// ```
+    /// # use readbuf::{ReadBuf, ReadBufError};
+    /// let mut buf = ReadBuf::with_capacity(4);
+    /// let mut reader = "Hello, world!".as_bytes();
+    ///
+    /// while buf.buf().len() < 13 {
+    ///     buf.read(&mut reader).unwrap();
+    /// }
+    /// assert_eq!(buf.buf(), b"Hello, world!");
+    ///
+    /// assert!(matches!(
+    ///     buf.read(&mut reader),
+    ///     Err(ReadBufError::Eof { buffered: 13 }),
+    /// ));
+    /// ```
+    pub fn read<R: Read>(&mut self, mut reader: R) -> Result<usize, ReadBufError> {
+        self.make_room()?;
+        let len = reader.read(&mut self.buf[self.end..])?;
+        self.filled(len)
+    }
+
+    /// Reads some more bytes into the buffer from a non-blocking reader,
+    /// returning whether any arrived.
+    ///
+    /// This is [`ReadBuf::read`] for event loops like those built on `mio`:
+    /// a reader that isn't ready yet gives [`FillOutcome::WouldBlock`] instead
+    /// of an error, and the end of the reader gives [`FillOutcome::Eof`], so
+    /// only real failures are returned as errors. Interrupted reads are
+    /// retried.
+    ///
+    /// # Errors
+    ///
+    /// If the buffer is full and already at its [`ReadBuf::max_capacity`],
+    /// [`ReadBufError::Full`] is returned without reading. Errors from the
+    /// reader other than [`io::ErrorKind::WouldBlock`] and
+    /// [`io::ErrorKind::Interrupted`] are returned as [`ReadBufError::Io`].
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::io::{self, Read};
+    /// # use readbuf::{FillOutcome, ReadBuf};
+    /// // A socket that has some bytes ready, and then no more for now
+    /// struct Socket(Option<&'static [u8]>);
+    ///
+    /// impl Read for Socket {
+    ///     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    ///         match self.0.take() {
+    ///             Some(mut bytes) => bytes.read(buf),
+    ///             None => Err(io::ErrorKind::WouldBlock.into()),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let mut buf = ReadBuf::new();
+    /// let mut socket = Socket(Some(b"+OK\r\n"));
+    ///
+    /// assert_eq!(buf.try_read(&mut socket).unwrap(), FillOutcome::Filled(5));
+    /// assert_eq!(buf.try_read(&mut socket).unwrap(), FillOutcome::WouldBlock);
+    /// assert_eq!(
+    ///     buf.try_read("".as_bytes()).unwrap(),
+    ///     FillOutcome::Eof { buffered: 5 },
+    /// );
+    /// ```
+    pub fn try_read<R: Read>(&mut self, mut reader: R) -> Result<FillOutcome, ReadBufError> {
+        loop {
+            match self.read(&mut reader) {
+                Ok(len) => return Ok(FillOutcome::Filled(len)),
+                Err(ReadBufError::Eof { buffered }) => return Ok(FillOutcome::Eof { buffered }),
+                Err(ReadBufError::Io(err)) => match err.kind() {
+                    io::ErrorKind::WouldBlock => return Ok(FillOutcome::WouldBlock),
+                    io::ErrorKind::Interrupted => {}
+                    _ => return Err(ReadBufError::Io(err)),
+                },
+                Err(err) => return Err(err),
             }
-            self.end -= self.start;
-            self.start = 0;
         }
+    }
 
-        let len = reader.read(&mut self.buf[self.end..])?;
-        self.end += len;
+    /// Reads some more bytes into the buffer, giving up once `deadline` has
+    /// passed.
+    ///
+    /// This sets a read timeout on `reader` for the time left until `deadline`,
+    /// and sets it back to what it was afterwards.
+    ///
+    /// `reader` must be in blocking mode. On Unix a read that times out fails
+    /// with [`io::ErrorKind::WouldBlock`], so that's taken to mean the deadline
+    /// passed, and a non-blocking reader that has nothing to read would be
+    /// reported as timing out right away. Use [`ReadBuf::try_read`] for
+    /// non-blocking readers instead.
+    ///
+    /// # Errors
+    ///
+    /// If `deadline` passes before any bytes arrive, [`ReadBufError::TimedOut`]
+    /// is returned. The bytes already buffered are kept, so the read can be
+    /// retried. Otherwise the same errors as
+    /// [`ReadBuf::read`] are returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::io::Write;
+    /// # use std::net::{TcpListener, TcpStream};
+    /// # use std::time::{Duration, Instant};
+    /// # use readbuf::{ReadBuf, ReadBufError};
+    /// # let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    /// # let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    /// # let (mut stream, _) = listener.accept().unwrap();
+    /// let mut buf = ReadBuf::new();
+    /// client.write_all(b"$5\r\nHel").unwrap();
+    /// buf.read(&mut stream).unwrap();
+    ///
+    /// // The rest of the frame doesn't arrive in time
+    /// let deadline = Instant::now() + Duration::from_millis(10);
+    /// assert!(matches!(
+    ///     buf.read_with_deadline(&mut stream, deadline),
+    ///     Err(ReadBufError::TimedOut),
+    /// ));
+    /// assert_eq!(buf.buf(), b"$5\r\nHel");
+    /// ```
+    pub fn read_with_deadline<R: Read + ReadTimeout>(
+        &mut self,
+        mut reader: R,
+        deadline: Instant,
+    ) -> Result<usize, ReadBufError> {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        if timeout.is_zero() {
+            return Err(ReadBufError::TimedOut);
+        }
+
+        let previous = reader.read_timeout()?;
+        reader.set_read_timeout(Some(timeout))?;
+        let result = self.read(&mut reader);
+        reader.set_read_timeout(previous)?;
+
+        result.map_err(|err| match err {
+            // Timeouts show up as `WouldBlock` on Unix
+            ReadBufError::Io(err)
+                if matches!(
+                    err.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                ReadBufError::TimedOut
+            }
+            err => err,
+        })
+    }
+
+    /// Reads into `head` and then into the buffer with a single
+    /// [`Read::read_vectored`] call, returning the total number of bytes read.
+    ///
+    /// This is useful when the rest of a payload should go straight to its
+    /// final destination: the payload fills `head`, and whatever comes after
+    /// it lands in the buffer to be parsed next, all in one syscall on
+    /// readers that support vectored reads.
+    ///
+    /// Like [`ReadBuf::read`], this is an inherent method that shadows
+    /// [`Read::read_vectored`] on the buffer itself.
+    ///
+    /// # Errors
+    ///
+    /// The same errors as [`ReadBuf::read`] are returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::io::IoSliceMut;
+    /// # use readbuf::ReadBuf;
+    /// let mut buf = ReadBuf::new();
+    /// let mut payload = [0; 5];
+    /// let mut reader = "Hello\r\n+OK\r\n".as_bytes();
+    ///
+    /// let len = buf
+    ///     .read_vectored(&mut reader, &mut [IoSliceMut::new(&mut payload)])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(len, 12);
+    /// assert_eq!(&payload, b"Hello");
+    /// assert_eq!(buf.buf(), b"\r\n+OK\r\n");
+    /// ```
+    pub fn read_vectored<R: Read>(
+        &mut self,
+        mut reader: R,
+        head: &mut [IoSliceMut<'_>],
+    ) -> Result<usize, ReadBufError> {
+        self.make_room()?;
+        let head_len: usize = head.iter().map(|slice| slice.len()).sum();
+
+        let mut bufs: Vec<IoSliceMut<'_>> = head.iter_mut().map(|s| IoSliceMut::new(s)).collect();
+        bufs.push(IoSliceMut::new(&mut self.buf[self.end..]));
+        let len = reader.read_vectored(&mut bufs)?;
+
+        let buffered = len.saturating_sub(head_len);
+        if len > 0 && buffered == 0 {
+            // Everything went into `head`, which isn't the end of the reader
+            self.record_read(0);
+            return Ok(len);
+        }
+        self.filled(buffered).map(|_| len)
+    }
+
+    /// Reads into the buffer until at least `n` bytes are buffered, returning
+    /// the number of bytes read.
+    ///
+    /// # Errors
+    ///
+    /// If the reader runs out before `n` bytes are buffered,
+    /// [`ReadBufError::Eof`] is returned. Whatever was read before then stays
+    /// buffered. Otherwise the same errors as [`ReadBuf::read`] are returned,
+    /// except that interrupted reads are retried.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::io::Read;
+    /// # use readbuf::{ReadBuf, ReadBufError};
+    /// let mut buf = ReadBuf::new();
+    /// // A reader that gives back "Hel", then "lo, ", then "world!"
+    /// let mut reader = b"Hel".chain(&b"lo, "[..]).chain(&b"world!"[..]);
+    ///
+    /// assert_eq!(buf.fill_at_least(5, &mut reader).unwrap(), 7);
+    /// assert_eq!(buf.buf(), b"Hello, ");
+    ///
+    /// assert!(matches!(
+    ///     buf.fill_at_least(20, &mut reader),
+    ///     Err(ReadBufError::Eof { buffered: 13 }),
+    /// ));
+    /// assert_eq!(buf.buf(), b"Hello, world!");
+    /// ```
+    pub fn fill_at_least<R: Read>(&mut self, n: usize, reader: R) -> Result<usize, ReadBufError> {
+        self.fill_until(reader, |buf| buf.len() >= n)
+    }
+
+    /// Reads into the buffer until `pred` returns `true` for the buffered
+    /// bytes, returning the number of bytes read.
+    ///
+    /// `pred` is checked before each read, so nothing is read if it's already
+    /// satisfied.
+    ///
+    /// # Errors
+    ///
+    /// The same errors as [`ReadBuf::fill_at_least`] are returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use readbuf::ReadBuf;
+    /// let mut buf = ReadBuf::new();
+    /// let mut reader = "+OK\r\n".as_bytes();
+    ///
+    /// buf.fill_until(&mut reader, |buf| buf.ends_with(b"\r\n")).unwrap();
+    /// assert_eq!(buf.buf(), b"+OK\r\n");
+    /// ```
+    pub fn fill_until<R: Read>(
+        &mut self,
+        mut reader: R,
+        mut pred: impl FnMut(&[u8]) -> bool,
+    ) -> Result<usize, ReadBufError> {
+        let mut total = 0;
+        while !pred(self.buf()) {
+            match self.read(&mut reader) {
+                Ok(len) => total += len,
+                Err(ReadBufError::Io(err)) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(total)
+    }
+
+    /// Reads some more bytes into the buffer from an async reader, returning
+    /// the number of bytes read.
+    ///
+    /// This is the same as [`ReadBuf::read`], but for a
+    /// [`tokio::io::AsyncRead`] like a `TcpStream`.
+    ///
+    /// # Errors
+    ///
+    /// The same errors as [`ReadBuf::read`] are returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use readbuf::ReadBuf;
+    /// # let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    /// # rt.block_on(async {
+    /// let mut buf = ReadBuf::new();
+    /// let mut reader = "+OK\r\n".as_bytes();
+    ///
+    /// assert_eq!(buf.read_async(&mut reader).await.unwrap(), 5);
+    /// assert_eq!(buf.buf(), b"+OK\r\n");
+    /// # });
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub async fn read_async<R>(&mut self, reader: &mut R) -> Result<usize, ReadBufError>
+    where
+        R: tokio::io::AsyncRead + Unpin + ?Sized,
+    {
+        self.fill_async::<runtime::Tokio, R>(reader).await
+    }
+
+    /// Reads some more bytes into the buffer from an async reader, returning
+    /// the number of bytes read.
+    ///
+    /// This is the same as [`ReadBuf::read_async`], but for the `AsyncRead` of
+    /// the `futures-io` crate, which is what readers from `async-std` and
+    /// `smol` implement.
+    ///
+    /// # Errors
+    ///
+    /// The same errors as [`ReadBuf::read`] are returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use readbuf::ReadBuf;
+    /// # let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    /// # rt.block_on(async {
+    /// let mut buf = ReadBuf::new();
+    /// let mut reader = "+OK\r\n".as_bytes();
+    ///
+    /// assert_eq!(buf.read_async_futures(&mut reader).await.unwrap(), 5);
+    /// assert_eq!(buf.buf(), b"+OK\r\n");
+    /// # });
+    /// ```
+    #[cfg(feature = "futures-io")]
+    pub async fn read_async_futures<R>(&mut self, reader: &mut R) -> Result<usize, ReadBufError>
+    where
+        R: futures_io::AsyncRead + Unpin + ?Sized,
+    {
+        self.fill_async::<runtime::FuturesIo, R>(reader).await
+    }
+
+    /// Reads some more bytes into the buffer from the async reader of any
+    /// runtime.
+    #[cfg(any(feature = "tokio", feature = "futures-io"))]
+    pub(crate) async fn fill_async<M, R>(&mut self, reader: &mut R) -> Result<usize, ReadBufError>
+    where
+        R: runtime::AsyncFill<M> + Unpin + ?Sized,
+    {
+        use std::pin::Pin;
+
+        self.make_room()?;
+        let len = std::future::poll_fn(|cx| {
+            Pin::new(&mut *reader).poll_fill(cx, &mut self.buf[self.end..])
+        })
+        .await?;
+        self.filled(len)
+    }
+
+    /// Reads some more bytes into the buffer from an async reader, giving up
+    /// once `deadline` has passed.
+    ///
+    /// # Errors
+    ///
+    /// The same errors as [`ReadBuf::read_with_deadline`] are returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::time::{Duration, Instant};
+    /// # use readbuf::{ReadBuf, ReadBufError};
+    /// # let rt = tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+    /// # rt.block_on(async {
+    /// let (_client, mut server) = tokio::io::duplex(64);
+    /// let mut buf = ReadBuf::new();
+    ///
+    /// let deadline = Instant::now() + Duration::from_millis(10);
+    /// assert!(matches!(
+    ///     buf.read_async_with_deadline(&mut server, deadline).await,
+    ///     Err(ReadBufError::TimedOut),
+    /// ));
+    /// # });
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub async fn read_async_with_deadline<R>(
+        &mut self,
+        reader: &mut R,
+        deadline: Instant,
+    ) -> Result<usize, ReadBufError>
+    where
+        R: tokio::io::AsyncRead + Unpin + ?Sized,
+    {
+        let deadline = tokio::time::Instant::from_std(deadline);
+        tokio::time::timeout_at(deadline, self.read_async(reader))
+            .await
+            .unwrap_or(Err(ReadBufError::TimedOut))
+    }
+
+    /// Compacts and grows the buffer as needed so there's space to read into.
+    fn make_room(&mut self) -> Result<(), ReadBufError> {
+        if self.end + self.compact_threshold > self.buf.len() {
+            self.compact();
+        }
+
+        if self.end == self.buf.len() {
+            self.grow(self.len() + 1)?;
+        }
+        Ok(())
+    }
+
+    /// Marks `len` freshly read bytes as buffered.
+    fn filled(&mut self, len: usize) -> Result<usize, ReadBufError> {
+        self.record_read(len);
 
         if len > 0 {
             Ok(len)
         } else {
-            Err(io::Error::from(io::ErrorKind::WriteZero))
+            event!(debug, buffered = self.len(), "reader reached its end");
+            Err(ReadBufError::Eof {
+                buffered: self.len(),
+            })
         }
     }
 
+    /// Marks `len` bytes as buffered after a read, updating the stats.
+    fn record_read(&mut self, len: usize) {
+        event!(
+            trace,
+            bytes = len,
+            buffered = self.len() + len,
+            "filled buffer"
+        );
+        self.end += len;
+        self.stats.fills += 1;
+        self.stats.bytes_read += len as u64;
+        self.stats.peak_len = self.stats.peak_len.max(self.len());
+    }
+
+    /// Doubles the capacity of the buffer until it can hold `needed` bytes,
+    /// keeping the buffered bytes.
+    fn grow(&mut self, needed: usize) -> Result<(), ReadBufError> {
+        let mut capacity = (self.buf.len() * 2).max(512);
+        while capacity < needed {
+            capacity *= 2;
+        }
+        if let Some(max_capacity) = self.max_capacity {
+            capacity = capacity.min(max_capacity);
+        }
+        if capacity < needed {
+            event!(debug, needed, capacity, "buffer full at its max capacity");
+            return Err(ReadBufError::Full);
+        }
+
+        event!(debug, from = self.buf.len(), to = capacity, "grew buffer");
+        let mut buf = zeroed(capacity);
+        buf[..self.end - self.start].copy_from_slice(self.buf());
+        self.buf = buf;
+        self.end -= self.start;
+        self.start = 0;
+        Ok(())
+    }
+
+    /// Appends `bytes` to the buffer, as if they had been read.
+    ///
+    /// The buffer is compacted or grown as needed to fit them.
+    ///
+    /// # Errors
+    ///
+    /// If `bytes` don't fit within the [`ReadBuf::max_capacity`],
+    /// [`ReadBufError::Full`] is returned and nothing is appended.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::io::Write;
+    /// # use readbuf::ReadBuf;
+    /// let mut buf = ReadBuf::with_capacity(4);
+    /// buf.extend_from_slice(b"+OK\r\n").unwrap();
+    /// write!(buf, ":{}\r\n", 12).unwrap();
+    ///
+    /// assert_eq!(buf.buf(), b"+OK\r\n:12\r\n");
+    /// ```
+    pub fn extend_from_slice(&mut self, bytes: &[u8]) -> Result<(), ReadBufError> {
+        if self.buf.len() - self.end < bytes.len() {
+            if self.spare_capacity() >= bytes.len() {
+                self.compact();
+            } else {
+                self.grow(self.len() + bytes.len())?;
+            }
+        }
+
+        self.buf[self.end..self.end + bytes.len()].copy_from_slice(bytes);
+        self.end += bytes.len();
+        self.stats.peak_len = self.stats.peak_len.max(self.len());
+        Ok(())
+    }
+
+    /// Moves the buffered bytes to the front of the buffer, making all of the
+    /// space taken up by consumed bytes available to read into.
+    ///
+    /// [`ReadBuf::read`] already does this when it's running out of space,
+    /// but calling it directly lets the copy happen at a more convenient time.
+    ///
+    /// # Examples
+    /// ```
+    /// # use readbuf::ReadBuf;
+    /// let mut buf = ReadBuf::with_capacity(16).compact_threshold(0);
+    /// buf.read("Hello, world!".as_bytes()).unwrap();
+    /// buf.consume(7);
+    ///
+    /// buf.compact();
+    /// assert_eq!(buf.buf(), b"world!");
+    /// ```
+    pub fn compact(&mut self) {
+        if self.start == 0 {
+            return;
+        }
+        self.stats.compactions += 1;
+        event!(
+            trace,
+            moved = self.len(),
+            from = self.start,
+            "compacted buffer"
+        );
+
+        // SAFETY: `self.start` and `self.end` are both valid indices into
+        // `self.buf`.
+        unsafe {
+            ptr::copy(
+                self.buf.as_ptr().add(self.start),
+                self.buf.as_mut_ptr(),
+                self.end - self.start,
+            );
+        }
+        self.end -= self.start;
+        self.start = 0;
+    }
+
+    /// Returns counters describing how the buffer has been used so far.
+    ///
+    /// # Examples
+    /// ```
+    /// # use readbuf::ReadBuf;
+    /// let mut buf = ReadBuf::with_capacity(16).compact_threshold(16);
+    /// buf.read("Hello".as_bytes()).unwrap();
+    /// buf.consume(3);
+    /// buf.read(", world!".as_bytes()).unwrap();
+    ///
+    /// let stats = buf.stats();
+    /// assert_eq!(stats.bytes_read, 13);
+    /// assert_eq!(stats.fills, 2);
+    /// assert_eq!(stats.compactions, 1);
+    /// assert_eq!(stats.peak_len, 10);
+    /// ```
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// Returns the number of bytes currently buffered.
+    ///
+    /// # Examples
+    /// ```
+    /// # use readbuf::ReadBuf;
+    /// let mut buf = ReadBuf::with_capacity(16);
+    /// assert!(buf.is_empty());
+    ///
+    /// buf.read("Hello".as_bytes()).unwrap();
+    /// assert_eq!(buf.len(), 5);
+    /// assert_eq!(buf.capacity(), 16);
+    /// assert_eq!(buf.spare_capacity(), 11);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Returns `true` if no bytes are buffered.
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Returns the total number of bytes the buffer can hold without growing.
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns the number of bytes that can be read into the buffer without
+    /// growing, including the space taken up by already consumed bytes.
+    pub fn spare_capacity(&self) -> usize {
+        self.buf.len() - self.len()
+    }
+
     /// Returns the bytes currently buffered.
     pub fn buf(&self) -> &[u8] {
         &self.buf[self.start..self.end]
     }
 
+    /// Continues parsing the frame at the front of the buffer with `parser`,
+    /// consuming and returning it once it's complete.
+    ///
+    /// If the frame isn't complete yet, `None` is returned and nothing is consumed,
+    /// and `parser` remembers how much of the frame it has already checked. After
+    /// calling [`ReadBuf::read`] for more data, calling this method again resumes
+    /// from there instead of starting over.
+    ///
+    /// # Errors
+    ///
+    /// If the frame is malformed, the same error as [`Frame::parse`] is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cursor::frame::{Frame, FrameParser};
+    /// # use readbuf::ReadBuf;
+    /// // A reader that only gives back a few bytes at a time
+    /// let data = b"$13\r\nHello, world!\r\n";
+    /// let mut chunks = data.chunks(4);
+    ///
+    /// let mut buf = ReadBuf::new();
+    /// let mut parser = FrameParser::new();
+    /// let frame = loop {
+    ///     if let Some(frame) = buf.parse_frame(&mut parser).unwrap() {
+    ///         break frame;
+    ///     }
+    ///     buf.read(chunks.next().unwrap()).unwrap();
+    /// };
+    ///
+    /// assert_eq!(frame, Frame::Bulk(b"Hello, world!".to_vec()));
+    /// assert!(buf.buf().is_empty());
+    /// ```
+    pub fn parse_frame(&mut self, parser: &mut FrameParser) -> Result<Option<Frame>, CursorError> {
+        match parser.parse(self.buf())? {
+            Some((frame, len)) => {
+                self.consume(len);
+                Ok(Some(frame))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Parses a value from the front of the buffer with `parse`, reading more
+    /// from `reader` until there's enough data, and consumes exactly the bytes
+    /// `parse` advanced past.
+    ///
+    /// `parse` is retried from the start of the buffer whenever it fails with
+    /// an error where [`CursorError::not_enough_data`] is `true`.
+    ///
+    /// # Errors
+    ///
+    /// If `parse` fails for any other reason, [`ReadFrameError::Cursor`] is
+    /// returned. If the reader ends with nothing buffered,
+    /// [`ReadFrameError::Closed`] is returned, but if it ends partway through
+    /// a value, [`ReadFrameError::Truncated`] is returned instead. If the
+    /// value doesn't fit in the buffer, [`ReadFrameError::Full`] is returned.
+    /// Errors from the reader are returned as [`ReadFrameError::Io`].
+    ///
+    /// # Examples
+    /// ```
+    /// # use readbuf::{ReadBuf, ReadFrameError};
+    /// let mut buf = ReadBuf::new();
+    /// let mut reader = "1\r\n2\r\n3".as_bytes();
+    ///
+    /// assert_eq!(buf.read_frame(&mut reader, |src| cursor::integer(src)).unwrap(), 1);
+    /// assert_eq!(buf.read_frame(&mut reader, |src| cursor::integer(src)).unwrap(), 2);
+    /// assert!(matches!(
+    ///     buf.read_frame(&mut reader, |src| cursor::integer(src)),
+    ///     Err(ReadFrameError::Truncated(1)),
+    /// ));
+    ///
+    /// buf.consume(1);
+    /// assert!(matches!(
+    ///     buf.read_frame(&mut reader, |src| cursor::integer(src)),
+    ///     Err(ReadFrameError::Closed),
+    /// ));
+    /// ```
+    pub fn read_frame<R: Read, T>(
+        &mut self,
+        mut reader: R,
+        mut parse: impl FnMut(&mut Cursor<&[u8]>) -> Result<T, CursorError>,
+    ) -> Result<T, ReadFrameError> {
+        loop {
+            let mut src = Cursor::new(self.buf());
+            match parse(&mut src) {
+                Ok(value) => {
+                    let len = src.position() as usize;
+                    self.consume(len);
+                    return Ok(value);
+                }
+                Err(err) if err.not_enough_data() => {
+                    event!(trace, error = %err, buffered = self.len(), "frame incomplete, reading more");
+                }
+                Err(err) => {
+                    event!(debug, error = %err, "malformed frame");
+                    return Err(ReadFrameError::Cursor(err));
+                }
+            }
+
+            match self.read(&mut reader) {
+                Ok(_) => {}
+                Err(ReadBufError::Io(err)) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+
+    /// Returns a [`Cursor`] over the buffered bytes that consumes however far
+    /// it advanced once [`Parse::commit`] is called.
+    ///
+    /// Dropping the [`Parse`] without committing leaves the buffer untouched,
+    /// so a parse that fails partway through can be retried after reading more.
+    ///
+    /// # Examples
+    /// ```
+    /// # use readbuf::ReadBuf;
+    /// let mut buf = ReadBuf::new();
+    /// buf.read("+OK\r\n:12".as_bytes()).unwrap();
+    ///
+    /// let mut src = buf.cursor();
+    /// assert_eq!(cursor::line(&mut src), Ok("+OK".as_bytes()));
+    /// src.commit();
+    /// assert_eq!(buf.buf(), b":12");
+    ///
+    /// // Not terminated yet, so don't commit
+    /// let mut src = buf.cursor();
+    /// assert!(cursor::line(&mut src).is_err());
+    /// drop(src);
+    /// assert_eq!(buf.buf(), b":12");
+    /// ```
+    pub fn cursor(&mut self) -> Parse<'_> {
+        Parse {
+            cursor: Cursor::new(&self.buf[self.start..self.end]),
+            start: &mut self.start,
+        }
+    }
+
+    /// Attaches `reader` to the buffer, returning a [`BufRead`] that reads
+    /// from the buffered bytes first and then from `reader`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::io::BufRead;
+    /// # use readbuf::ReadBuf;
+    /// let mut buf = ReadBuf::new();
+    /// buf.read("first\nsec".as_bytes()).unwrap();
+    ///
+    /// let reader = "ond\n".as_bytes();
+    /// let lines: Vec<String> = buf.attach(reader).lines().map(Result::unwrap).collect();
+    /// assert_eq!(lines, ["first", "second"]);
+    /// ```
+    pub fn attach<R: Read>(&mut self, reader: R) -> Attached<'_, R> {
+        Attached { buf: self, reader }
+    }
+
+    /// Consumes the first `n` buffered bytes, returning them without copying.
+    ///
+    /// The returned [`Bytes`] shares memory with the buffer, which
+    /// gives up the space it was using, so the [`ReadBuf::capacity`] goes
+    /// down by however much was split off or consumed before it. The next
+    /// read grows the buffer back if it needs to.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if there aren't `n` bytes in the buffer.
+    ///
+    /// # Examples
+    /// ```
+    /// # use readbuf::ReadBuf;
+    /// let mut buf = ReadBuf::new();
+    /// buf.read("$5\r\nHello\r\n".as_bytes()).unwrap();
+    ///
+    /// buf.consume(4);
+    /// let payload = buf.split_to(5);
+    /// buf.consume(2);
+    ///
+    /// assert_eq!(payload, "Hello");
+    /// assert!(buf.is_empty());
+    /// ```
+    #[cfg(feature = "bytes")]
+    pub fn split_to(&mut self, n: usize) -> Bytes {
+        assert!(self.len() >= n, "not enough bytes to split off");
+        let head = self.buf.split_to(self.start + n).freeze();
+        let split = head.slice(self.start..);
+        self.end -= self.start + n;
+        self.start = 0;
+        split
+    }
+
     /// Marks `amt` bytes as consumed.
     ///
     /// # Panics
@@ -72,6 +968,339 @@ impl ReadBuf {
     /// This method panics if there aren't `amt` bytes in the buffer.
     pub fn consume(&mut self, amt: usize) {
         assert!(self.end - self.start >= amt, "not enough bytes to consume");
+        event!(trace, bytes = amt, "consumed from buffer");
         self.start += amt;
     }
 }
+
+/// Counters describing how a [`ReadBuf`] has been used.
+///
+/// This is returned by [`ReadBuf::stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Stats {
+    /// The total number of bytes read into the buffer.
+    pub bytes_read: u64,
+    /// The number of reads into the buffer, including ones that hit the end.
+    pub fills: u64,
+    /// The number of times buffered bytes were moved to the front.
+    pub compactions: u64,
+    /// The most bytes that have been buffered at once.
+    pub peak_len: usize,
+}
+
+/// The outcome of a successful [`ReadBuf::try_read`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FillOutcome {
+    /// This many bytes were read into the buffer.
+    Filled(usize),
+    /// The reader has no bytes ready yet, so it should be polled again once
+    /// it's readable.
+    WouldBlock,
+    /// The reader has reached its end, with this many bytes still buffered.
+    Eof { buffered: usize },
+}
+
+/// An error from reading into a [`ReadBuf`].
+#[derive(Debug)]
+pub enum ReadBufError {
+    /// The reader reached its end, with the number of bytes still buffered.
+    ///
+    /// If `buffered` is 0, then the reader ended cleanly between frames.
+    /// Otherwise it ended partway through one.
+    Eof { buffered: usize },
+    /// The buffer is full and can't grow any more.
+    Full,
+    /// The deadline passed before any bytes arrived.
+    TimedOut,
+    /// The reader failed.
+    Io(io::Error),
+}
+
+impl From<io::Error> for ReadBufError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ReadBufError> for io::Error {
+    fn from(err: ReadBufError) -> Self {
+        match err {
+            ReadBufError::Eof { .. } => io::ErrorKind::UnexpectedEof.into(),
+            ReadBufError::Full => io::Error::new(io::ErrorKind::OutOfMemory, err.to_string()),
+            ReadBufError::TimedOut => io::ErrorKind::TimedOut.into(),
+            ReadBufError::Io(err) => err,
+        }
+    }
+}
+
+impl fmt::Display for ReadBufError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Eof { buffered: 0 } => "reached end of reader".fmt(f),
+            Self::Eof { buffered } => {
+                write!(f, "reached end of reader with {buffered} bytes buffered")
+            }
+            Self::Full => "buffer is full at its maximum capacity".fmt(f),
+            Self::TimedOut => "timed out".fmt(f),
+            Self::Io(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ReadBufError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// A reader that supports read timeouts, for [`ReadBuf::read_with_deadline`].
+pub trait ReadTimeout {
+    /// Returns the current read timeout.
+    fn read_timeout(&self) -> io::Result<Option<Duration>>;
+
+    /// Sets the read timeout, where `None` means reads block indefinitely.
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()>;
+}
+
+impl ReadTimeout for TcpStream {
+    fn read_timeout(&self) -> io::Result<Option<Duration>> {
+        TcpStream::read_timeout(self)
+    }
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        TcpStream::set_read_timeout(self, timeout)
+    }
+}
+
+#[cfg(unix)]
+impl ReadTimeout for std::os::unix::net::UnixStream {
+    fn read_timeout(&self) -> io::Result<Option<Duration>> {
+        std::os::unix::net::UnixStream::read_timeout(self)
+    }
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        std::os::unix::net::UnixStream::set_read_timeout(self, timeout)
+    }
+}
+
+impl<T: ReadTimeout + ?Sized> ReadTimeout for &T {
+    fn read_timeout(&self) -> io::Result<Option<Duration>> {
+        (**self).read_timeout()
+    }
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        (**self).set_read_timeout(timeout)
+    }
+}
+
+impl<T: ReadTimeout + ?Sized> ReadTimeout for &mut T {
+    fn read_timeout(&self) -> io::Result<Option<Duration>> {
+        (**self).read_timeout()
+    }
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        (**self).set_read_timeout(timeout)
+    }
+}
+
+/// An error from [`ReadBuf::read_frame`].
+#[derive(Debug)]
+pub enum ReadFrameError {
+    /// The reader ended cleanly, with no bytes left over.
+    Closed,
+    /// The reader ended partway through a value, with the number of bytes
+    /// left in the buffer.
+    Truncated(usize),
+    /// The bytes in the buffer couldn't be parsed.
+    Cursor(CursorError),
+    /// The value didn't fit in the buffer.
+    Full,
+    /// The deadline passed before a whole value arrived.
+    TimedOut,
+    /// The reader failed.
+    Io(io::Error),
+}
+
+impl From<CursorError> for ReadFrameError {
+    fn from(err: CursorError) -> Self {
+        Self::Cursor(err)
+    }
+}
+
+impl From<ReadBufError> for ReadFrameError {
+    fn from(err: ReadBufError) -> Self {
+        match err {
+            ReadBufError::Eof { buffered: 0 } => Self::Closed,
+            ReadBufError::Eof { buffered } => Self::Truncated(buffered),
+            ReadBufError::Full => Self::Full,
+            ReadBufError::TimedOut => Self::TimedOut,
+            ReadBufError::Io(err) => Self::Io(err),
+        }
+    }
+}
+
+impl From<io::Error> for ReadFrameError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl fmt::Display for ReadFrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Closed => "connection closed".fmt(f),
+            Self::Truncated(len) => {
+                write!(f, "connection closed with {len} bytes of a partial frame")
+            }
+            Self::Cursor(err) => err.fmt(f),
+            Self::Full => "frame doesn't fit in the buffer".fmt(f),
+            Self::TimedOut => "timed out".fmt(f),
+            Self::Io(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ReadFrameError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Cursor(err) => Some(err),
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Read for ReadBuf {
+    /// Reads buffered bytes into `out`, consuming them.
+    ///
+    /// Once no bytes are buffered, this returns `Ok(0)`.
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        let len = self.buf().len().min(out.len());
+        out[..len].copy_from_slice(&self.buf()[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+impl BufRead for ReadBuf {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(self.buf())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        ReadBuf::consume(self, amt);
+    }
+}
+
+impl Write for ReadBuf {
+    /// Appends all of `bytes` to the buffer with [`ReadBuf::extend_from_slice`].
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.extend_from_slice(bytes)?;
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A [`ReadBuf`] with a reader attached to it.
+///
+/// This is returned by [`ReadBuf::attach`].
+#[derive(Debug)]
+pub struct Attached<'a, R> {
+    buf: &'a mut ReadBuf,
+    reader: R,
+}
+
+impl<R> Attached<'_, R> {
+    /// Detaches the reader, returning it back.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> Read for Attached<'_, R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        let len = {
+            let buf = self.fill_buf()?;
+            let len = buf.len().min(out.len());
+            out[..len].copy_from_slice(&buf[..len]);
+            len
+        };
+        self.buf.consume(len);
+        Ok(len)
+    }
+}
+
+impl<R: Read> BufRead for Attached<'_, R> {
+    /// Returns the buffered bytes, reading more from the reader if there
+    /// aren't any.
+    ///
+    /// Unlike [`ReadBuf::read`], reaching the end of the reader isn't an error
+    /// here, and just returns an empty slice.
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.buf.buf().is_empty() {
+            self.buf.make_room()?;
+            let len = self.reader.read(&mut self.buf.buf[self.buf.end..])?;
+            self.buf.record_read(len);
+        }
+        Ok(self.buf.buf())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.buf.consume(amt);
+    }
+}
+
+/// A [`Cursor`] over the bytes buffered in a [`ReadBuf`].
+///
+/// This is returned by [`ReadBuf::cursor`].
+#[derive(Debug)]
+pub struct Parse<'a> {
+    cursor: Cursor<&'a [u8]>,
+    start: &'a mut usize,
+}
+
+impl Parse<'_> {
+    /// Consumes the bytes the cursor advanced past, returning how many there
+    /// were.
+    ///
+    /// If the position was set past the end of the buffered bytes, only the
+    /// buffered bytes are consumed.
+    ///
+    /// # Examples
+    /// ```
+    /// # use readbuf::ReadBuf;
+    /// let mut buf = ReadBuf::new();
+    /// buf.read("+OK\r\n".as_bytes()).unwrap();
+    ///
+    /// let mut src = buf.cursor();
+    /// src.set_position(100);
+    /// assert_eq!(src.commit(), 5);
+    /// assert!(buf.is_empty());
+    /// ```
+    pub fn commit(self) -> usize {
+        let buffered = self.cursor.get_ref().len();
+        let len = usize::try_from(self.cursor.position()).map_or(buffered, |pos| pos.min(buffered));
+        *self.start += len;
+        len
+    }
+}
+
+impl<'a> Deref for Parse<'a> {
+    type Target = Cursor<&'a [u8]>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.cursor
+    }
+}
+
+impl DerefMut for Parse<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.cursor
+    }
+}
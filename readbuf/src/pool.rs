@@ -0,0 +1,120 @@
+//! Reusing [`ReadBuf`] allocations across connections.
+
+use crate::ReadBuf;
+use std::sync::Mutex;
+
+/// The number of size buckets, each twice as large as the one before.
+const BUCKETS: usize = 4;
+
+/// A pool of [`ReadBuf`]s to reuse instead of allocating a new one for every
+/// connection.
+///
+/// Buffers are kept in buckets by capacity: the base capacity, and then 2, 4,
+/// and 8 times that. A buffer that grew past the largest bucket isn't kept
+/// when it's recycled, so the memory held by the pool shrinks back once a
+/// burst of large frames is over.
+///
+/// The pool can be shared between threads.
+///
+/// # Examples
+/// ```
+/// # use readbuf::pool::BufPool;
+/// let pool = BufPool::new(4096, 16);
+///
+/// let mut buf = pool.take();
+/// buf.read("+OK\r\n".as_bytes()).unwrap();
+/// pool.recycle(buf);
+///
+/// // The same allocation comes back, but empty
+/// let buf = pool.take();
+/// assert!(buf.is_empty());
+/// assert_eq!(buf.capacity(), 4096);
+/// assert_eq!(pool.idle(), 0);
+/// ```
+#[derive(Debug)]
+pub struct BufPool {
+    capacity: usize,
+    max_idle: usize,
+    buckets: Mutex<[Vec<ReadBuf>; BUCKETS]>,
+}
+
+impl BufPool {
+    /// Creates a new [`BufPool`] of buffers with the given base capacity,
+    /// keeping at most `max_idle` unused buffers in each bucket.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `capacity` is 0.
+    pub fn new(capacity: usize, max_idle: usize) -> Self {
+        assert!(capacity > 0, "buffer capacity must be nonzero");
+        BufPool {
+            capacity,
+            max_idle,
+            buckets: Mutex::default(),
+        }
+    }
+
+    /// Takes a buffer with the base capacity out of the pool, allocating a new
+    /// one if there aren't any.
+    pub fn take(&self) -> ReadBuf {
+        self.take_with_capacity(self.capacity)
+    }
+
+    /// Takes a buffer with at least `capacity` out of the pool, allocating a
+    /// new one if there aren't any.
+    ///
+    /// # Examples
+    /// ```
+    /// # use readbuf::pool::BufPool;
+    /// # use readbuf::ReadBuf;
+    /// let pool = BufPool::new(1024, 16);
+    /// pool.recycle(ReadBuf::with_capacity(4096));
+    ///
+    /// assert_eq!(pool.take_with_capacity(3000).capacity(), 4096);
+    /// assert_eq!(pool.take_with_capacity(3000).capacity(), 3000);
+    /// ```
+    pub fn take_with_capacity(&self, capacity: usize) -> ReadBuf {
+        let mut buckets = self.buckets.lock().unwrap();
+        let first = self.bucket(capacity).unwrap_or(0);
+        for bucket in &mut buckets[first..] {
+            if let Some(i) = bucket.iter().position(|buf| buf.capacity() >= capacity) {
+                return bucket.swap_remove(i);
+            }
+        }
+        ReadBuf::with_capacity(capacity.max(self.capacity))
+    }
+
+    /// Returns `buf` to the pool to be reused.
+    ///
+    /// The buffered bytes are discarded, and the buffer's settings and
+    /// [`ReadBuf::stats`] are reset. If `buf` is too small or too large for any
+    /// bucket, or its bucket is already full, it's dropped instead.
+    pub fn recycle(&self, buf: ReadBuf) {
+        let Some(i) = self.bucket(buf.capacity()) else {
+            return;
+        };
+
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets[i].len() < self.max_idle {
+            buckets[i].push(ReadBuf {
+                buf: buf.buf,
+                ..ReadBuf::with_capacity(0)
+            });
+        }
+    }
+
+    /// Returns the number of unused buffers in the pool.
+    pub fn idle(&self) -> usize {
+        self.buckets.lock().unwrap().iter().map(Vec::len).sum()
+    }
+
+    /// Returns the bucket for buffers with `capacity`, or `None` if they're
+    /// smaller than the base capacity or larger than the largest bucket.
+    fn bucket(&self, capacity: usize) -> Option<usize> {
+        if capacity < self.capacity {
+            return None;
+        }
+        let i = (capacity / self.capacity).ilog2() as usize;
+        (i < BUCKETS).then_some(i)
+    }
+}
@@ -0,0 +1,497 @@
+//! Reading whole frames from a reader.
+
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+use crate::runtime::AsyncFill;
+use crate::{ReadBuf, ReadBufError, ReadFrameError, ReadTimeout};
+use cursor::frame::{Frame, FrameParser};
+use std::io::{self, Cursor, Read};
+use std::time::Instant;
+
+/// Reads frames from a reader, handling buffering and refilling.
+///
+/// This owns a reader and a [`ReadBuf`], and parses frames with a
+/// [`FrameParser`] so that frames arriving in pieces aren't re-checked from
+/// the start on every read.
+///
+/// # Examples
+/// ```
+/// # use cursor::frame::Frame;
+/// # use readbuf::FrameReader;
+/// let mut reader = FrameReader::new("+OK\r\n:12\r\n".as_bytes());
+///
+/// assert_eq!(reader.next_frame().unwrap(), Some(Frame::Simple("OK".to_string())));
+/// assert_eq!(reader.next_frame().unwrap(), Some(Frame::Integer(12)));
+/// assert_eq!(reader.next_frame().unwrap(), None);
+/// ```
+#[derive(Debug)]
+pub struct FrameReader<R> {
+    reader: R,
+    buf: ReadBuf,
+    parser: FrameParser,
+    recover: bool,
+    recovering: bool,
+    max_frame_len: u32,
+}
+
+impl<R: Read> FrameReader<R> {
+    /// Creates a new [`FrameReader`] with a [`ReadBuf::new`] buffer.
+    pub fn new(reader: R) -> Self {
+        Self::with_buf(reader, ReadBuf::new())
+    }
+
+    /// Creates a new [`FrameReader`] that reads into `buf`.
+    ///
+    /// Any bytes already in `buf` are read before anything from `reader`.
+    pub fn with_buf(reader: R, buf: ReadBuf) -> Self {
+        FrameReader {
+            reader,
+            buf,
+            parser: FrameParser::new(),
+            recover: false,
+            recovering: false,
+            max_frame_len: u32::MAX,
+        }
+    }
+
+    /// Sets whether to skip past a malformed frame after returning its error.
+    ///
+    /// By default, a malformed frame is left in the buffer, so every call to
+    /// [`FrameReader::next_frame`] after the first error returns it again.
+    /// With this enabled, the error is still returned, but the next call
+    /// first discards everything up to the next `\r\n` with
+    /// [`cursor::recover_to_next_line`], then carries on from there.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cursor::frame::Frame;
+    /// # use readbuf::{FrameReader, ReadFrameError};
+    /// let mut reader = FrameReader::new(":oops\r\n+OK\r\n".as_bytes()).recover(true);
+    ///
+    /// assert!(matches!(reader.next_frame(), Err(ReadFrameError::Cursor(_))));
+    /// assert_eq!(reader.next_frame().unwrap(), Some(Frame::Simple("OK".to_string())));
+    /// ```
+    pub fn recover(mut self, recover: bool) -> Self {
+        self.recover = recover;
+        self
+    }
+
+    /// Sets the largest payload that [`FrameReader::next_length_prefixed`]
+    /// accepts, which is `u32::MAX` by default.
+    ///
+    /// A larger length prefix is rejected as soon as it arrives, before
+    /// buffering any of the payload.
+    pub fn max_frame_len(mut self, max_frame_len: u32) -> Self {
+        self.max_frame_len = max_frame_len;
+        self
+    }
+
+    /// Reads the next frame, returning `None` if the reader ended cleanly
+    /// between frames.
+    ///
+    /// # Errors
+    ///
+    /// If the reader ends partway through a frame,
+    /// [`ReadFrameError::Truncated`] is returned. Otherwise the same errors
+    /// as [`ReadBuf::read_frame`] are returned.
+    pub fn next_frame(&mut self) -> Result<Option<Frame>, ReadFrameError> {
+        self.next_frame_with(|buf, reader| buf.read(reader))
+    }
+
+    /// Reads the next frame like [`FrameReader::next_frame`], giving up once
+    /// `deadline` has passed.
+    ///
+    /// # Errors
+    ///
+    /// If `deadline` passes first, [`ReadFrameError::TimedOut`] is returned,
+    /// and whatever part of the frame already arrived stays buffered for the
+    /// next call. Otherwise the same errors as [`FrameReader::next_frame`] are
+    /// returned.
+    pub fn next_frame_with_deadline(
+        &mut self,
+        deadline: Instant,
+    ) -> Result<Option<Frame>, ReadFrameError>
+    where
+        R: ReadTimeout,
+    {
+        self.next_frame_with(|buf, reader| buf.read_with_deadline(reader, deadline))
+    }
+
+    /// Reads the next frame like [`FrameReader::next_frame`], along with every
+    /// other complete frame that's already buffered, appending them to
+    /// `frames` and returning how many there were.
+    ///
+    /// This only reads from the reader while there isn't a single complete
+    /// frame buffered, so a batch of pipelined commands is parsed straight
+    /// from the buffer, each frame once, before the reader is touched again.
+    /// Returns 0 if the reader ended cleanly between frames.
+    ///
+    /// # Errors
+    ///
+    /// The same errors as [`FrameReader::next_frame`] are returned for the
+    /// first frame. A malformed frame after that ends the batch early, and its
+    /// error is returned by the next call instead.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cursor::frame::Frame;
+    /// # use readbuf::FrameReader;
+    /// let mut reader = FrameReader::new("+PING\r\n+PING\r\n:1\r\n".as_bytes());
+    ///
+    /// let mut frames = Vec::new();
+    /// assert_eq!(reader.read_frames_batch(&mut frames).unwrap(), 3);
+    /// assert_eq!(frames[2], Frame::Integer(1));
+    ///
+    /// assert_eq!(reader.read_frames_batch(&mut frames).unwrap(), 0);
+    /// ```
+    pub fn read_frames_batch(&mut self, frames: &mut Vec<Frame>) -> Result<usize, ReadFrameError> {
+        let Some(frame) = self.next_frame()? else {
+            return Ok(0);
+        };
+        frames.push(frame);
+
+        let mut count = 1;
+        let mut src = Cursor::new(self.buf.buf());
+        let mut parsed = 0;
+        while let Ok(frame) = Frame::parse(&mut src) {
+            frames.push(frame);
+            count += 1;
+            parsed = src.position() as usize;
+        }
+        self.buf.consume(parsed);
+        Ok(count)
+    }
+
+    /// Reads the next line, without the `\r\n`, returning `None` if the reader
+    /// ended cleanly between lines.
+    ///
+    /// This can be mixed with [`FrameReader::next_frame`], like for a protocol
+    /// that switches framing partway through. Reading a line forgets any
+    /// progress on a frame, along with any malformed frame waiting to be
+    /// skipped by [`FrameReader::recover`], so the next frame is read from
+    /// wherever the line ended.
+    ///
+    /// # Errors
+    ///
+    /// The same errors as [`FrameReader::next_frame`] are returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cursor::frame::Frame;
+    /// # use readbuf::FrameReader;
+    /// let mut reader = FrameReader::new("PING\r\n:1\r\nQUIT\r\n".as_bytes());
+    ///
+    /// assert_eq!(reader.next_line().unwrap(), Some(b"PING".to_vec()));
+    /// assert_eq!(reader.next_frame().unwrap(), Some(Frame::Integer(1)));
+    /// assert_eq!(reader.next_line().unwrap(), Some(b"QUIT".to_vec()));
+    /// assert_eq!(reader.next_line().unwrap(), None);
+    /// ```
+    pub fn next_line(&mut self) -> Result<Option<Vec<u8>>, ReadFrameError> {
+        self.next_with(|src| cursor::line(src).map(<[u8]>::to_vec))
+    }
+
+    /// Reads the next payload prefixed by its length as a big-endian `u32`,
+    /// returning `None` if the reader ended cleanly between payloads.
+    ///
+    /// This is for protocols framed with [`cursor::length_prefixed`] instead
+    /// of RESP.
+    ///
+    /// # Errors
+    ///
+    /// If the length prefix is larger than [`FrameReader::max_frame_len`],
+    /// [`ReadFrameError::Cursor`] is returned with
+    /// `CursorError::FrameTooLarge`, and the frame is left in the buffer.
+    /// Otherwise the same errors as [`FrameReader::next_frame`] are returned.
+    ///
+    /// Like [`FrameReader::next_line`], this can be mixed with
+    /// [`FrameReader::next_frame`].
+    ///
+    /// # Examples
+    /// ```
+    /// # use cursor::CursorError;
+    /// # use readbuf::{FrameReader, ReadFrameError};
+    /// let mut reader =
+    ///     FrameReader::new(b"\0\0\0\x02hi\0\0\x10\0".as_slice()).max_frame_len(1024);
+    ///
+    /// assert_eq!(reader.next_length_prefixed().unwrap(), Some(b"hi".to_vec()));
+    /// assert!(matches!(
+    ///     reader.next_length_prefixed(),
+    ///     Err(ReadFrameError::Cursor(CursorError::FrameTooLarge)),
+    /// ));
+    /// ```
+    pub fn next_length_prefixed(&mut self) -> Result<Option<Vec<u8>>, ReadFrameError> {
+        let max_len = self.max_frame_len;
+        self.next_with(|src| cursor::length_prefixed_bounded(src, max_len).map(<[u8]>::to_vec))
+    }
+
+    /// Reads the next value with `parse` instead of as a frame, returning
+    /// `None` if the reader ended cleanly between values.
+    fn next_with<T>(
+        &mut self,
+        parse: impl FnMut(&mut Cursor<&[u8]>) -> Result<T, cursor::CursorError>,
+    ) -> Result<Option<T>, ReadFrameError> {
+        // Any progress on a frame, or skipping a malformed one, is about bytes
+        // that `parse` may consume, so frames start over afterwards
+        self.parser.reset();
+        self.recovering = false;
+        match self.buf.read_frame(&mut self.reader, parse) {
+            Ok(value) => Ok(Some(value)),
+            Err(ReadFrameError::Closed) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Reads the next frame, using `read` to read more bytes into the buffer.
+    fn next_frame_with(
+        &mut self,
+        mut read: impl FnMut(&mut ReadBuf, &mut R) -> Result<usize, ReadBufError>,
+    ) -> Result<Option<Frame>, ReadFrameError> {
+        loop {
+            if self.recovering {
+                let mut src = self.buf.cursor();
+                let result = cursor::recover_to_next_line(&mut src);
+                event!(debug, ?result, "skipping malformed frame");
+                src.commit();
+                self.recovering = result.is_err();
+            }
+
+            if !self.recovering {
+                match self.buf.parse_frame(&mut self.parser) {
+                    Ok(Some(frame)) => return Ok(Some(frame)),
+                    Ok(None) => {
+                        event!(
+                            trace,
+                            checked = self.parser.checked(),
+                            buffered = self.buf.len(),
+                            "frame incomplete, reading more",
+                        );
+                    }
+                    Err(err) => {
+                        event!(debug, error = %err, recover = self.recover, "malformed frame");
+                        // The parser is partway through the malformed frame
+                        self.parser.reset();
+                        self.recovering = self.recover;
+                        return Err(err.into());
+                    }
+                }
+            }
+
+            if !filled(read(&mut self.buf, &mut self.reader))? {
+                return Ok(None);
+            }
+        }
+    }
+}
+
+impl<R> FrameReader<R> {
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Returns the buffer holding bytes that have been read but not parsed.
+    pub fn buf(&self) -> &ReadBuf {
+        &self.buf
+    }
+
+    /// Consumes the [`FrameReader`], returning the reader and the buffer.
+    pub fn into_parts(self) -> (R, ReadBuf) {
+        (self.reader, self.buf)
+    }
+}
+
+/// Reads frames from an async reader, handling buffering and refilling.
+///
+/// This is the same as [`FrameReader`], but for an async reader from either
+/// tokio or the `futures-io` crate, as described by [`AsyncFill`].
+///
+/// # Cancel safety
+///
+/// [`AsyncFrameReader::read_frame`] is cancel safe. If its future is dropped
+/// before it completes, for example because another branch of a
+/// `tokio::select!` finished first, no bytes that were already read are lost,
+/// and the next call picks up the partial frame where it left off.
+///
+/// # Examples
+/// ```
+/// # use cursor::frame::Frame;
+/// # use readbuf::reader::AsyncFrameReader;
+/// # use tokio::io::AsyncWriteExt;
+/// # let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// # #[cfg(feature = "tokio")]
+/// # rt.block_on(async {
+/// let (mut client, server) = tokio::io::duplex(64);
+/// let mut reader = AsyncFrameReader::new(server);
+///
+/// // Only part of the frame has arrived, so the other branch finishes first
+/// client.write_all(b"$5\r\nHel").await.unwrap();
+/// tokio::select! {
+///     _ = reader.read_frame() => unreachable!(),
+///     _ = tokio::task::yield_now() => {}
+/// }
+///
+/// // That didn't lose the partial frame
+/// client.write_all(b"lo\r\n").await.unwrap();
+/// assert_eq!(reader.read_frame().await.unwrap(), Some(Frame::Bulk(b"Hello".to_vec())));
+///
+/// drop(client);
+/// assert_eq!(reader.read_frame().await.unwrap(), None);
+/// # });
+/// ```
+/// A reader that implements both `AsyncRead` traits, like `&[u8]`, has to
+/// name the runtime to read with:
+/// ```
+/// # use cursor::frame::Frame;
+/// # use readbuf::reader::AsyncFrameReader;
+/// # #[cfg(feature = "futures-io")]
+/// # use readbuf::runtime::FuturesIo;
+/// # let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// # #[cfg(feature = "futures-io")]
+/// # rt.block_on(async {
+/// let mut reader = AsyncFrameReader::new("+OK\r\n".as_bytes());
+///
+/// let frame = reader.read_frame::<FuturesIo>().await.unwrap();
+/// assert_eq!(frame, Some(Frame::Simple("OK".to_string())));
+/// # });
+/// ```
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+#[derive(Debug)]
+pub struct AsyncFrameReader<R> {
+    reader: R,
+    buf: ReadBuf,
+    parser: FrameParser,
+}
+
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+impl<R> AsyncFrameReader<R> {
+    /// Creates a new [`AsyncFrameReader`] with a [`ReadBuf::new`] buffer.
+    pub fn new(reader: R) -> Self {
+        Self::with_buf(reader, ReadBuf::new())
+    }
+
+    /// Creates a new [`AsyncFrameReader`] that reads into `buf`.
+    ///
+    /// Any bytes already in `buf` are read before anything from `reader`.
+    pub fn with_buf(reader: R, buf: ReadBuf) -> Self {
+        AsyncFrameReader {
+            reader,
+            buf,
+            parser: FrameParser::new(),
+        }
+    }
+
+    /// Reads the next frame, returning `None` if the reader ended cleanly
+    /// between frames.
+    ///
+    /// This method is cancel safe, see the [type-level docs](AsyncFrameReader)
+    /// for details.
+    ///
+    /// # Errors
+    ///
+    /// The same errors as [`FrameReader::next_frame`] are returned.
+    pub async fn read_frame<M>(&mut self) -> Result<Option<Frame>, ReadFrameError>
+    where
+        R: AsyncFill<M> + Unpin,
+    {
+        loop {
+            match self.buf.parse_frame(&mut self.parser) {
+                Ok(Some(frame)) => return Ok(Some(frame)),
+                Ok(None) => {}
+                Err(err) => {
+                    // The parser is partway through the malformed frame
+                    self.parser.reset();
+                    return Err(err.into());
+                }
+            }
+            // Cancelling this only drops the pending read, which hasn't put
+            // anything in the buffer yet.
+            let result = self.buf.fill_async(&mut self.reader).await;
+            if !filled(result)? {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Reads the next frame like [`AsyncFrameReader::read_frame`], giving up
+    /// once `deadline` has passed.
+    ///
+    /// # Errors
+    ///
+    /// The same errors as [`FrameReader::next_frame_with_deadline`] are
+    /// returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::time::{Duration, Instant};
+    /// # use readbuf::reader::AsyncFrameReader;
+    /// # use readbuf::ReadFrameError;
+    /// # use tokio::io::AsyncWriteExt;
+    /// # let rt = tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+    /// # rt.block_on(async {
+    /// let (mut client, server) = tokio::io::duplex(64);
+    /// let mut reader = AsyncFrameReader::new(server);
+    ///
+    /// client.write_all(b"$5\r\nHel").await.unwrap();
+    /// let deadline = Instant::now() + Duration::from_millis(10);
+    /// assert!(matches!(
+    ///     reader.read_frame_with_deadline(deadline).await,
+    ///     Err(ReadFrameError::TimedOut),
+    /// ));
+    /// assert_eq!(reader.buf().buf(), b"$5\r\nHel");
+    /// # });
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub async fn read_frame_with_deadline<M>(
+        &mut self,
+        deadline: Instant,
+    ) -> Result<Option<Frame>, ReadFrameError>
+    where
+        R: AsyncFill<M> + Unpin,
+    {
+        let deadline = tokio::time::Instant::from_std(deadline);
+        tokio::time::timeout_at(deadline, self.read_frame())
+            .await
+            .unwrap_or(Err(ReadFrameError::TimedOut))
+    }
+}
+
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+impl<R> AsyncFrameReader<R> {
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Returns the buffer holding bytes that have been read but not parsed.
+    pub fn buf(&self) -> &ReadBuf {
+        &self.buf
+    }
+
+    /// Consumes the [`AsyncFrameReader`], returning the reader and the buffer.
+    pub fn into_parts(self) -> (R, ReadBuf) {
+        (self.reader, self.buf)
+    }
+}
+
+/// Checks the result of reading into a buffer, returning `false` if the reader
+/// ended cleanly with nothing buffered.
+///
+/// An interrupted read counts as a successful one, so that the caller just
+/// tries again.
+fn filled(result: Result<usize, ReadBufError>) -> Result<bool, ReadFrameError> {
+    match result {
+        Ok(_) => Ok(true),
+        Err(ReadBufError::Io(err)) if err.kind() == io::ErrorKind::Interrupted => Ok(true),
+        Err(ReadBufError::Eof { buffered: 0 }) => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
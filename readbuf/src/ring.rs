@@ -0,0 +1,136 @@
+//! A fixed-size circular buffer for reading into.
+
+use crate::ReadBufError;
+use std::io::{IoSliceMut, Read};
+
+/// Circular buffer for reading into.
+///
+/// Unlike [`ReadBuf`](crate::ReadBuf), which moves buffered bytes back to
+/// the front to make room, a [`RingBuf`] reads into the free space on both
+/// sides of the buffered bytes, wrapping around the end. It never copies
+/// during a read, so the buffered bytes may be split in two, which is why
+/// [`RingBuf::bufs`] returns a pair of slices.
+///
+/// The capacity is fixed when the buffer is created.
+#[derive(Debug)]
+pub struct RingBuf {
+    buf: Box<[u8]>,
+    head: usize,
+    len: usize,
+}
+
+impl RingBuf {
+    /// Creates a new [`RingBuf`] with the given capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        RingBuf {
+            buf: vec![0; capacity].into_boxed_slice(),
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Reads some more bytes into the buffer, returning the number of bytes
+    /// read.
+    ///
+    /// When the free space wraps around the end of the buffer, both parts are
+    /// passed to a single [`Read::read_vectored`] call.
+    ///
+    /// # Errors
+    ///
+    /// If the buffer is full, [`ReadBufError::Full`] is returned without
+    /// reading. Otherwise the same errors as
+    /// [`ReadBuf::read`](crate::ReadBuf::read) are returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use readbuf::ring::RingBuf;
+    /// let mut buf = RingBuf::with_capacity(8);
+    /// buf.read("+OK\r\n:1".as_bytes()).unwrap();
+    /// buf.consume(5);
+    ///
+    /// // The free space wraps around to the front
+    /// buf.read("2\r\n".as_bytes()).unwrap();
+    /// assert_eq!(buf.bufs(), (&b":12"[..], &b"\r\n"[..]));
+    ///
+    /// assert_eq!(buf.make_contiguous(), b":12\r\n");
+    /// ```
+    pub fn read<R: Read>(&mut self, mut reader: R) -> Result<usize, ReadBufError> {
+        if self.len == self.buf.len() {
+            return Err(ReadBufError::Full);
+        }
+
+        let tail = self.tail();
+        let len = if tail < self.head {
+            reader.read(&mut self.buf[tail..self.head])?
+        } else {
+            let (front, back) = self.buf.split_at_mut(tail);
+            let front = &mut front[..self.head];
+            reader.read_vectored(&mut [IoSliceMut::new(back), IoSliceMut::new(front)])?
+        };
+        self.len += len;
+
+        if len > 0 {
+            Ok(len)
+        } else {
+            Err(ReadBufError::Eof { buffered: self.len })
+        }
+    }
+
+    /// Returns the bytes currently buffered, in order, as two slices.
+    ///
+    /// The second slice is only non-empty when the bytes wrap around the end
+    /// of the buffer.
+    pub fn bufs(&self) -> (&[u8], &[u8]) {
+        let first_end = self.buf.len().min(self.head + self.len);
+        let second_end = self.len - (first_end - self.head);
+        (&self.buf[self.head..first_end], &self.buf[..second_end])
+    }
+
+    /// Rearranges the buffer so the buffered bytes are contiguous, returning
+    /// them.
+    ///
+    /// This only copies if the bytes currently wrap around the end of the
+    /// buffer.
+    pub fn make_contiguous(&mut self) -> &[u8] {
+        if self.head + self.len > self.buf.len() {
+            self.buf.rotate_left(self.head);
+            self.head = 0;
+        }
+        &self.buf[self.head..self.head + self.len]
+    }
+
+    /// Returns the number of bytes currently buffered.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no bytes are buffered.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the total number of bytes the buffer can hold.
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Marks `amt` bytes as consumed.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if there aren't `amt` bytes in the buffer.
+    pub fn consume(&mut self, amt: usize) {
+        assert!(self.len >= amt, "not enough bytes to consume");
+        self.len -= amt;
+        self.head = if self.len == 0 {
+            0
+        } else {
+            (self.head + amt) % self.buf.len()
+        };
+    }
+
+    /// Returns the index one past the last buffered byte.
+    fn tail(&self) -> usize {
+        (self.head + self.len) % self.buf.len()
+    }
+}
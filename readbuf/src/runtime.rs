@@ -0,0 +1,56 @@
+//! Reading from the async readers of different runtimes.
+
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// An async reader that a [`ReadBuf`](crate::ReadBuf) can read into.
+///
+/// This is implemented for tokio's `AsyncRead` with the `tokio` feature, and
+/// for the `AsyncRead` of the `futures-io` crate with the `futures-io` feature,
+/// which is what readers from `async-std` and `smol` implement. `Runtime` is
+/// [`Tokio`] or [`FuturesIo`], and only tells the two implementations apart,
+/// so it's inferred unless a reader implements both.
+pub trait AsyncFill<Runtime> {
+    /// Attempts to read into `buf`, returning how many bytes were read.
+    fn poll_fill(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>>;
+}
+
+/// Marks the [`AsyncFill`] implementation for tokio's `AsyncRead`.
+#[cfg(feature = "tokio")]
+#[derive(Debug)]
+pub enum Tokio {}
+
+/// Marks the [`AsyncFill`] implementation for the `futures-io` crate's
+/// `AsyncRead`.
+#[cfg(feature = "futures-io")]
+#[derive(Debug)]
+pub enum FuturesIo {}
+
+#[cfg(feature = "tokio")]
+impl<R: tokio::io::AsyncRead + ?Sized> AsyncFill<Tokio> for R {
+    fn poll_fill(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut buf = tokio::io::ReadBuf::new(buf);
+        std::task::ready!(tokio::io::AsyncRead::poll_read(self, cx, &mut buf))?;
+        Poll::Ready(Ok(buf.filled().len()))
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<R: futures_io::AsyncRead + ?Sized> AsyncFill<FuturesIo> for R {
+    fn poll_fill(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        futures_io::AsyncRead::poll_read(self, cx, buf)
+    }
+}
@@ -0,0 +1,166 @@
+//! Buffering frames for writing.
+
+use cursor::encode::{put_frame, put_line};
+use cursor::frame::Frame;
+use std::io::{self, Write};
+
+/// Buffer for writing from.
+///
+/// This is the writing counterpart to [`ReadBuf`](crate::ReadBuf): frames
+/// and lines are encoded into the buffer, and then written out with
+/// [`WriteBuf::flush`], which keeps track of how much a short write got
+/// through so nothing is written twice or skipped.
+///
+/// The buffer doesn't flush on its own, but [`WriteBuf::needs_flush`] says
+/// when more than the watermark is waiting to be written.
+///
+/// # Examples
+/// ```
+/// # use cursor::frame::Frame;
+/// # use readbuf::WriteBuf;
+/// let mut buf = WriteBuf::new();
+/// buf.write_frame(&Frame::Simple("OK".to_string()));
+/// buf.write_line(b"PING");
+///
+/// let mut socket = Vec::new();
+/// buf.flush(&mut socket).unwrap();
+///
+/// assert_eq!(socket, b"+OK\r\nPING\r\n");
+/// assert!(buf.is_empty());
+/// ```
+#[derive(Debug)]
+pub struct WriteBuf {
+    buf: Vec<u8>,
+    pos: usize,
+    watermark: usize,
+}
+
+impl Default for WriteBuf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WriteBuf {
+    /// Creates a new [`WriteBuf`] with a watermark of 8192.
+    pub fn new() -> Self {
+        Self::with_watermark(8192)
+    }
+
+    /// Creates a new [`WriteBuf`] with the given watermark.
+    pub fn with_watermark(watermark: usize) -> Self {
+        WriteBuf {
+            buf: Vec::new(),
+            pos: 0,
+            watermark,
+        }
+    }
+
+    /// Encodes `frame` into the buffer.
+    pub fn write_frame(&mut self, frame: &Frame) {
+        put_frame(&mut self.buf, frame).expect("writing to a `Vec` can't fail");
+    }
+
+    /// Writes `line` followed by `\r\n` into the buffer.
+    pub fn write_line(&mut self, line: &[u8]) {
+        put_line(&mut self.buf, line).expect("writing to a `Vec` can't fail");
+    }
+
+    /// Returns the bytes that haven't been written out yet.
+    pub fn buf(&self) -> &[u8] {
+        &self.buf[self.pos..]
+    }
+
+    /// Returns the number of bytes that haven't been written out yet.
+    pub fn len(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+
+    /// Returns `true` if everything has been written out.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if at least the watermark's worth of bytes haven't been
+    /// written out yet.
+    ///
+    /// # Examples
+    /// ```
+    /// # use readbuf::WriteBuf;
+    /// let mut buf = WriteBuf::with_watermark(8);
+    ///
+    /// buf.write_line(b"PING");
+    /// assert!(!buf.needs_flush());
+    /// buf.write_line(b"PING");
+    /// assert!(buf.needs_flush());
+    /// ```
+    pub fn needs_flush(&self) -> bool {
+        self.len() >= self.watermark
+    }
+
+    /// Makes a single write of the buffered bytes to `writer`, returning how
+    /// many were written.
+    ///
+    /// This is useful for non-blocking writers, where [`WriteBuf::flush`]
+    /// would give up on [`io::ErrorKind::WouldBlock`] anyways.
+    ///
+    /// # Errors
+    ///
+    /// Errors from `writer` are returned, and nothing is marked as written.
+    pub fn flush_some<W: Write>(&mut self, mut writer: W) -> io::Result<usize> {
+        let len = writer.write(self.buf())?;
+        self.pos += len;
+        if self.pos == self.buf.len() {
+            self.buf.clear();
+            self.pos = 0;
+        }
+        Ok(len)
+    }
+
+    /// Writes all of the buffered bytes to `writer`, retrying after short
+    /// writes, and then flushes `writer`.
+    ///
+    /// # Errors
+    ///
+    /// If `writer` fails or stops accepting bytes, the error is returned and
+    /// whatever wasn't written stays buffered, so calling this again picks up
+    /// where it left off.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::io::{self, Write};
+    /// # use readbuf::WriteBuf;
+    /// // A writer that only takes 3 bytes at a time
+    /// struct Slow(Vec<u8>);
+    ///
+    /// impl Write for Slow {
+    ///     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    ///         let len = buf.len().min(3);
+    ///         self.0.extend_from_slice(&buf[..len]);
+    ///         Ok(len)
+    ///     }
+    ///
+    ///     fn flush(&mut self) -> io::Result<()> {
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let mut buf = WriteBuf::new();
+    /// buf.write_line(b"Hello, world!");
+    ///
+    /// let mut socket = Slow(Vec::new());
+    /// buf.flush(&mut socket).unwrap();
+    /// assert_eq!(socket.0, b"Hello, world!\r\n");
+    /// ```
+    pub fn flush<W: Write>(&mut self, mut writer: W) -> io::Result<()> {
+        while !self.is_empty() {
+            match self.flush_some(&mut writer) {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(_) => {}
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        writer.flush()
+    }
+}